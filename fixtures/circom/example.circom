@@ -0,0 +1,18 @@
+pragma circom 2.0.0;
+
+// Source of example.r1cs / example.wtns, for the Pallas prime (`--prime pallas`).
+//
+// wires: 0 = one, 1 = d, 2 = a, 3 = b, 4 = c, 5 = m
+// witness: a = 7, b = 3, c = 2
+template Example() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal output d;
+    signal m;
+
+    m <== a * b;
+    d <== (m + 3 * c - 1) * (a - b);
+}
+
+component main {public [a]} = Example();
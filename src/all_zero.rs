@@ -0,0 +1,290 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use std::marker::PhantomData;
+
+/// IsZeroChip for a vector: all_zero = 1 if every v_i is 0, and any_nonzero =
+/// 1 - all_zero. Instead of an inverse per element it witnesses weights w_i and
+/// one flag z = all_zero with
+///
+///     Σ w_i·v_i = 1 - z        z·v_i = 0 for every i
+///
+/// If every v_i is 0 the sum is 0, so z = 1. If some v_j is nonzero then z = 0
+/// and the prover needs Σ w_i·v_i = 1, e.g. w_j = 1/v_j and the other weights 0.
+/// So there is a single inversion, and z is a bit without a separate check.
+/// With no values at all the result is the constant all_zero = 1.
+///
+///     v   | w   | acc               | z | out   | q_all | q_first | q_next | q_last
+///    -------------------------------------------------------------------------------
+///     v_0 | w_0 | w_0·v_0           | z |       |   1   |    1    |   0    |   0
+///     v_1 | w_1 | acc_0 + w_1·v_1   | z |       |   1   |    0    |   1    |   0
+///     ... | ... | ...               | z |       |   1   |    0    |   1    |   0
+///     v_n | w_n | 1 - z             | z | 1 - z |   1   |    0    |   1    |   1
+#[derive(Debug, Clone)]
+pub struct AllZeroConfig {
+    pub v: Column<Advice>,
+    pub w: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub z: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_all: Selector,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub q_last: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AllZeroChip<F: FieldExt> {
+    config: AllZeroConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> AllZeroChip<F> {
+    pub fn construct(config: AllZeroConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> AllZeroConfig {
+        let v = meta.advice_column();
+        let w = meta.advice_column();
+        let acc = meta.advice_column();
+        let z = meta.advice_column();
+        let out = meta.advice_column();
+        let q_all = meta.selector();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let q_last = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(v);
+        meta.enable_equality(z);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("z·v = 0", |meta| {
+            let s = meta.query_selector(q_all);
+            let v = meta.query_advice(v, Rotation::cur());
+            let z = meta.query_advice(z, Rotation::cur());
+            vec![s * z * v]
+        });
+
+        meta.create_gate("first weighted sum", |meta| {
+            let s = meta.query_selector(q_first);
+            let v = meta.query_advice(v, Rotation::cur());
+            let w = meta.query_advice(w, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (w * v - acc)]
+        });
+
+        meta.create_gate("next weighted sum", |meta| {
+            let s = meta.query_selector(q_next);
+            let v = meta.query_advice(v, Rotation::cur());
+            let w = meta.query_advice(w, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let z_prev = meta.query_advice(z, Rotation::prev());
+            let z = meta.query_advice(z, Rotation::cur());
+            vec![s.clone() * (acc_prev + w * v - acc), s * (z_prev - z)]
+        });
+
+        meta.create_gate("Σ w·v = 1 - z", |meta| {
+            let s = meta.query_selector(q_last);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let z = meta.query_advice(z, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let not_z = Expression::Constant(F::one()) - z;
+            vec![s.clone() * (not_z.clone() - acc), s * (not_z - out)]
+        });
+
+        AllZeroConfig {
+            v,
+            w,
+            acc,
+            z,
+            out,
+            q_all,
+            q_first,
+            q_next,
+            q_last,
+            instance,
+            constant,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.v, 0, || value),
+        )
+    }
+
+    /// Returns the cells holding [all_zero, any_nonzero].
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        if values.is_empty() {
+            return layouter.assign_region(
+                || "all zero of none",
+                |mut region| {
+                    Ok([
+                        region.assign_advice_from_constant(|| "z", self.config.z, 0, F::one())?,
+                        region.assign_advice_from_constant(
+                            || "any nonzero",
+                            self.config.out,
+                            0,
+                            F::zero(),
+                        )?,
+                    ])
+                },
+            );
+        }
+        let last = values.len() - 1;
+
+        // The only nonzero weight is 1/v_j for the first nonzero v_j
+        let first_nonzero = values
+            .iter()
+            .enumerate()
+            .fold(Value::known(None), |found, (i, v)| {
+                found
+                    .zip(v.value())
+                    .map(|(found, v)| found.or(Some(i).filter(|_| *v != F::zero())))
+            });
+        let z = first_nonzero.map(|j| F::from(j.is_none() as u64));
+
+        layouter.assign_region(
+            || "all zero",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut z_cell = None;
+
+                for (row, cell) in values.iter().enumerate() {
+                    self.config.q_all.enable(&mut region, row)?;
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_next.enable(&mut region, row)?;
+                    }
+
+                    let v = cell.copy_advice(|| "v", &mut region, self.config.v, row)?;
+                    let w = first_nonzero.zip(v.value()).map(|(j, v)| match j {
+                        Some(j) if j == row => v.invert().unwrap(),
+                        _ => F::zero(),
+                    });
+                    region.assign_advice(|| "w", self.config.w, row, || w)?;
+
+                    acc = acc + w * v.value();
+                    region.assign_advice(|| "acc", self.config.acc, row, || acc)?;
+                    z_cell = Some(region.assign_advice(|| "z", self.config.z, row, || z)?);
+                }
+
+                self.config.q_last.enable(&mut region, last)?;
+                let any_nonzero = z.map(|z| F::one() - z);
+                let out = region.assign_advice(
+                    || "any nonzero",
+                    self.config.out,
+                    last,
+                    || any_nonzero,
+                )?;
+
+                Ok([z_cell.unwrap(), out])
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        values: Vec<Value<F>>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = AllZeroConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            AllZeroChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = AllZeroChip::construct(config);
+
+            let cells = self
+                .values
+                .iter()
+                .map(|v| chip.load_private(layouter.namespace(|| "v"), *v))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let [all_zero, any_nonzero] = chip.assign(layouter.namespace(|| "all zero"), &cells)?;
+
+            chip.expose_public(layouter.namespace(|| "all zero"), &all_zero, 0)?;
+            chip.expose_public(layouter.namespace(|| "any nonzero"), &any_nonzero, 1)
+        }
+    }
+
+    fn run(values: &[u64], all_zero: bool) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+        };
+        let public_input = vec![Fp::from(all_zero as u64), Fp::from(!all_zero as u64)];
+        MockProver::run(6, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_all_zero() {
+        run(&[], true).assert_satisfied();
+        assert!(run(&[], false).verify().is_err());
+        run(&[0], true).assert_satisfied();
+        run(&[0; 20], true).assert_satisfied();
+        run(&[7], false).assert_satisfied();
+        run(&[0, 0, 0, 5, 0, 9], false).assert_satisfied();
+        run(&[1, 2, 3, 4, 5, 6, 7, 8], false).assert_satisfied();
+
+        let mut values = [0; 20];
+        values[19] = 3;
+        run(&values, false).assert_satisfied();
+
+        assert!(run(&[0; 20], false).verify().is_err());
+        assert!(run(&values, true).verify().is_err());
+        assert!(run(&[7], true).verify().is_err());
+    }
+}
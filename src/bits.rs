@@ -0,0 +1,412 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+/// Decomposes a value into n bits, with a running sum from the most
+/// significant bit:
+///
+///     bit     | acc                   | q_first | q_next
+///    ----------------------------------------------------
+///     b_n-1   | b_n-1                 |    1    |   0
+///     b_n-2   | 2·acc + b_n-2         |    0    |   1
+///     ...     | ...                   |    0    |   1
+///     b_0     | 2·acc + b_0 = value   |    0    |   1
+///
+/// Both gates constrain the bit to be boolean, and the last acc is copy
+/// constrained to the value, so the value must be below 2^n.
+#[derive(Debug, Clone)]
+pub struct BitsConfig {
+    pub bit: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BitsChip<F: FieldExt> {
+    config: BitsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BitsChip<F> {
+    pub fn construct(config: BitsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BitsConfig {
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(bit);
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        let is_bit = |b: Expression<F>| b.clone() * (Expression::Constant(F::one()) - b);
+
+        meta.create_gate("first bit", |meta| {
+            let s = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s.clone() * is_bit(bit.clone()), s * (bit - acc)]
+        });
+
+        meta.create_gate("running sum", |meta| {
+            let s = meta.query_selector(q_next);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let two = Expression::Constant(F::from(2));
+            vec![
+                s.clone() * is_bit(bit.clone()),
+                s * (two * acc_prev + bit - acc),
+            ]
+        });
+
+        BitsConfig {
+            bit,
+            acc,
+            q_first,
+            q_next,
+            instance,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.acc, 0, || value),
+        )
+    }
+
+    /// Returns the n bits of value in the given order, for 0 < n <= F::NUM_BITS.
+    /// With n = F::NUM_BITS the bits are not unique: a value v below 2^n - p
+    /// also decomposes as v + p.
+    pub fn decompose(
+        &self,
+        layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        n: usize,
+        order: BitOrder,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if n == 0 || n > F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        // Most significant first, as assigned. The repr is little-endian.
+        let bits: Vec<_> = (0..n)
+            .rev()
+            .map(|i| {
+                value.value().map(|v| {
+                    let byte = v.to_repr().as_ref()[i / 8];
+                    F::from(((byte >> (i % 8)) & 1) as u64)
+                })
+            })
+            .collect();
+
+        let mut bits = self.assign(layouter, value, &bits)?;
+        if order == BitOrder::LittleEndian {
+            bits.reverse();
+        }
+        Ok(bits)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    // Assigns the bits, most significant first, and returns them in that order
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        bits: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "bit decomposition",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut acc_cell = None;
+                let mut cells = vec![];
+
+                for (row, bit) in bits.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_next.enable(&mut region, row)?;
+                    }
+
+                    cells.push(region.assign_advice(|| "bit", self.config.bit, row, || *bit)?);
+                    acc = acc * Value::known(F::from(2)) + bit;
+                    acc_cell =
+                        Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                }
+
+                region.constrain_equal(acc_cell.unwrap().cell(), value.cell())?;
+                Ok(cells)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pyth::{pythChip, pythConfig};
+    use crate::select::{SelectChip, SelectConfig};
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        pasta::{group::ff::PrimeField, Fp},
+        plonk::Circuit,
+    };
+
+    // Exposes the n bits of value. With bits given the chip's own
+    // decomposition is skipped.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        value: Value<F>,
+        n: usize,
+        order: Option<BitOrder>,
+        bits: Option<Vec<Value<F>>>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = BitsConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                n: self.n,
+                order: self.order,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            BitsChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BitsChip::construct(config);
+
+            let value = chip.load_private(layouter.namespace(|| "value"), self.value)?;
+            let bits = match &self.bits {
+                Some(bits) => chip.assign(layouter.namespace(|| "bits"), &value, bits)?,
+                None => {
+                    let order = self.order.unwrap();
+                    chip.decompose(layouter.namespace(|| "bits"), &value, self.n, order)?
+                }
+            };
+
+            for (row, bit) in bits.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "bit"), bit, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(value: Fp, n: usize, order: BitOrder, bits: &[u64]) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            value: Value::known(value),
+            n,
+            order: Some(order),
+            bits: None,
+        };
+        let public_input = bits.iter().map(|b| Fp::from(*b)).collect();
+        MockProver::run(8, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_bits() {
+        let le = [1, 0, 1, 1, 0, 0, 0, 0];
+        let be = [0, 0, 0, 0, 1, 1, 0, 1];
+        run(Fp::from(13), 8, BitOrder::LittleEndian, &le).assert_satisfied();
+        run(Fp::from(13), 8, BitOrder::BigEndian, &be).assert_satisfied();
+        run(Fp::from(1), 1, BitOrder::BigEndian, &[1]).assert_satisfied();
+
+        let max = Fp::from_u128(u128::MAX);
+        run(max, 128, BitOrder::BigEndian, &[1; 128]).assert_satisfied();
+
+        assert!(run(Fp::from(13), 8, BitOrder::LittleEndian, &be)
+            .verify()
+            .is_err());
+
+        // 2^8 does not fit in 8 bits, and -1 in none
+        assert!(run(Fp::from(256), 8, BitOrder::BigEndian, &[0; 8])
+            .verify()
+            .is_err());
+        let minus_one = run(-Fp::one(), 128, BitOrder::BigEndian, &[1; 128]);
+        assert!(minus_one.verify().is_err());
+    }
+
+    #[test]
+    fn test_bits_wide() {
+        // 2^129 is past the lower 128 bits
+        let value = Fp::from_u128(1 << 127) * Fp::from(4);
+        let mut bits = [0; 130];
+        bits[0] = 1;
+        let circuit = MyCircuit {
+            value: Value::known(value),
+            n: 130,
+            order: Some(BitOrder::BigEndian),
+            bits: None,
+        };
+        let public_input = bits.iter().map(|b| Fp::from(*b)).collect();
+        MockProver::run(8, &circuit, vec![public_input])
+            .unwrap()
+            .assert_satisfied();
+
+        // Every field element fits in NUM_BITS bits
+        let n = Fp::NUM_BITS as usize;
+        let circuit = MyCircuit {
+            value: Value::known(-Fp::one()),
+            n,
+            order: Some(BitOrder::LittleEndian),
+            bits: None,
+        };
+        // p - 1 = 2^254 + 0x224698fc094cf91b992d30ed00000000
+        let low: u128 = 0x224698fc094cf91b992d30ed00000000;
+        let p_minus_one = (0..n).map(|i| match i {
+            254 => Fp::one(),
+            i if i < 128 => Fp::from(((low >> i) & 1) as u64),
+            _ => Fp::zero(),
+        });
+        MockProver::run(9, &circuit, vec![p_minus_one.collect()])
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    #[test]
+    fn test_bits_invalid_n() {
+        for n in [0, Fp::NUM_BITS as usize + 1] {
+            let circuit = MyCircuit {
+                value: Value::known(Fp::from(13)),
+                n,
+                order: Some(BitOrder::LittleEndian),
+                bits: None,
+            };
+            let result = MockProver::run(9, &circuit, vec![vec![]]);
+            assert!(matches!(result, Err(Error::Synthesis)));
+        }
+    }
+
+    #[test]
+    fn test_bits_not_boolean() {
+        let run = |bits: &[u64]| {
+            let circuit = MyCircuit {
+                value: Value::known(Fp::from(5)),
+                n: bits.len(),
+                order: None,
+                bits: Some(bits.iter().map(|b| Value::known(Fp::from(*b))).collect()),
+            };
+            let public_input = bits.iter().map(|b| Fp::from(*b)).collect();
+            MockProver::run(8, &circuit, vec![public_input]).unwrap()
+        };
+
+        run(&[1, 0, 1]).assert_satisfied();
+
+        // 1·4 + 0·2 + 1 = 0·4 + 2·2 + 1
+        assert!(run(&[0, 2, 1]).verify().is_err());
+    }
+
+    // x^e by square-and-multiply over the bits of e, with SelectChip picking
+    // acc·x or acc for each bit
+    #[derive(Default)]
+    struct PowCircuit<F> {
+        x: Value<F>,
+        e: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for PowCircuit<F> {
+        type Config = (BitsConfig, SelectConfig<F>, pythConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            (
+                BitsChip::configure(meta),
+                SelectChip::configure(meta),
+                pythChip::configure(meta),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let bits = BitsChip::construct(config.0);
+            let select = SelectChip::construct(config.1);
+            let pyth = pythChip::construct(config.2);
+
+            let x = pyth.load_private(layouter.namespace(|| "x"), self.x)?;
+            let e = bits.load_private(layouter.namespace(|| "e"), self.e)?;
+            let e_bits = bits.decompose(layouter.namespace(|| "e"), &e, 8, BitOrder::BigEndian)?;
+
+            let mut acc = pyth.load_constant(layouter.namespace(|| "1"), F::one())?;
+            for bit in e_bits.iter() {
+                let square = pyth.mul(layouter.namespace(|| "acc^2"), &acc, &acc)?;
+                let times_x = pyth.mul(layouter.namespace(|| "acc^2 * x"), &square, &x)?;
+                acc = select.select(
+                    layouter.namespace(|| "bit ? acc^2 * x : acc^2"),
+                    bit,
+                    &times_x,
+                    &square,
+                )?;
+            }
+
+            pyth.expose_public(layouter.namespace(|| "x^e"), &acc, 0)
+        }
+    }
+
+    #[test]
+    fn test_bits_square_and_multiply() {
+        let run = |x: u64, e: u64, out: Fp| {
+            let circuit = PowCircuit {
+                x: Value::known(Fp::from(x)),
+                e: Value::known(Fp::from(e)),
+            };
+            MockProver::run(8, &circuit, vec![vec![], vec![], vec![out]]).unwrap()
+        };
+
+        run(3, 13, Fp::from(1_594_323)).assert_satisfied();
+        run(3, 0, Fp::one()).assert_satisfied();
+        run(2, 255, Fp::from(2).pow(&[255, 0, 0, 0])).assert_satisfied();
+        assert!(run(3, 12, Fp::from(1_594_323)).verify().is_err());
+    }
+}
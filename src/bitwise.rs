@@ -0,0 +1,347 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::table::{BitwiseOp, BitwiseTableConfig};
+
+/// XOR, AND and OR of words split into limbs of BITS bits, most significant
+/// first. Every limb row is one lookup of (op, a_i, b_i, out_i) into the
+/// BitwiseTableConfig, which also range checks the limbs, and running sums
+/// recompose the three words:
+///
+///     a   | b   | out   | acc_a          | acc_b | acc_out | q_first | q_next
+///    --------------------------------------------------------------------------
+///     a_0 | b_0 | out_0 | a_0            | b_0   | out_0   |    1    |   0
+///     a_1 | b_1 | out_1 | acc·2^BITS+a_1 | ...   | ...     |    0    |   1
+///
+/// The last row of acc_a and acc_b is copy constrained to the inputs, so they
+/// must be below 2^(BITS·limbs). One limb is a single row.
+#[derive(Debug, Clone)]
+pub(super) struct BitwiseConfig<F: FieldExt, const BITS: usize> {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub acc: [Column<Advice>; 3],
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub q_xor: Selector,
+    pub q_and: Selector,
+    pub q_or: Selector,
+    pub instance: Column<Instance>,
+    pub(super) table: BitwiseTableConfig<F, BITS>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BitwiseChip<F: FieldExt, const BITS: usize> {
+    config: BitwiseConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseChip<F, BITS> {
+    pub fn construct(config: BitwiseConfig<F, BITS>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BitwiseConfig<F, BITS> {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let acc = [(); 3].map(|_| meta.advice_column());
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let q_xor = meta.complex_selector();
+        let q_and = meta.complex_selector();
+        let q_or = meta.complex_selector();
+        let instance = meta.instance_column();
+        let table = BitwiseTableConfig::configure(meta);
+
+        for column in [a, b, out].iter().chain(acc.iter()) {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+
+        let limbs = [a, b, out];
+
+        meta.create_gate("first limb", |meta| {
+            let s = meta.query_selector(q_first);
+            limbs
+                .iter()
+                .zip(acc.iter())
+                .map(|(limb, acc)| {
+                    let limb = meta.query_advice(*limb, Rotation::cur());
+                    let acc = meta.query_advice(*acc, Rotation::cur());
+                    s.clone() * (limb - acc)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("running sum", |meta| {
+            let s = meta.query_selector(q_next);
+            let base = Expression::Constant(F::from(1 << BITS));
+            limbs
+                .iter()
+                .zip(acc.iter())
+                .map(|(limb, acc)| {
+                    let limb = meta.query_advice(*limb, Rotation::cur());
+                    let acc_prev = meta.query_advice(*acc, Rotation::prev());
+                    let acc = meta.query_advice(*acc, Rotation::cur());
+                    s.clone() * (acc_prev * base.clone() + limb - acc)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.lookup(|meta| {
+            let q_xor = meta.query_selector(q_xor);
+            let q_and = meta.query_selector(q_and);
+            let q_or = meta.query_selector(q_or);
+            let s = q_xor.clone() + q_and.clone() + q_or.clone();
+            let tag = [
+                (q_xor, BitwiseOp::Xor),
+                (q_and, BitwiseOp::And),
+                (q_or, BitwiseOp::Or),
+            ]
+            .into_iter()
+            .fold(Expression::Constant(F::zero()), |tag, (q, op)| {
+                tag + q * Expression::Constant(F::from(op.tag()))
+            });
+
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![
+                (tag, table.tag),
+                (s.clone() * a, table.a),
+                (s.clone() * b, table.b),
+                (s * out, table.out),
+            ]
+        });
+
+        BitwiseConfig {
+            a,
+            b,
+            out,
+            acc,
+            q_first,
+            q_next,
+            q_xor,
+            q_and,
+            q_or,
+            instance,
+            table,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.load(layouter)
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    /// Returns the cell holding a op b, for a and b of limbs·BITS bits (at most
+    /// 64). Other widths are an Error::Synthesis.
+    pub fn bitwise(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        limbs: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if limbs == 0 || limbs * BITS > 64 {
+            return Err(Error::Synthesis);
+        }
+        let selector = match op {
+            BitwiseOp::Xor => self.config.q_xor,
+            BitwiseOp::And => self.config.q_and,
+            BitwiseOp::Or => self.config.q_or,
+        };
+
+        // Most significant limb first
+        let limb = |value: &AssignedCell<F, F>, i: usize| {
+            let shift = BITS * (limbs - 1 - i);
+            value
+                .value()
+                .map(move |v| (v.get_lower_128() >> shift) as u64 & ((1 << BITS) - 1))
+        };
+
+        layouter.assign_region(
+            || "bitwise",
+            |mut region| {
+                let mut acc = [Value::known(F::zero()); 3];
+                let mut acc_cells = vec![];
+
+                for row in 0..limbs {
+                    selector.enable(&mut region, row)?;
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_next.enable(&mut region, row)?;
+                    }
+
+                    let (a, b) = (limb(a, row), limb(b, row));
+                    let out = a.zip(b).map(|(a, b)| op.apply(a, b));
+                    let columns = [self.config.a, self.config.b, self.config.out];
+
+                    acc_cells.clear();
+                    for (i, (column, limb)) in columns.iter().zip([a, b, out]).enumerate() {
+                        let limb = limb.map(F::from);
+                        region.assign_advice(|| "limb", *column, row, || limb)?;
+
+                        acc[i] = acc[i] * Value::known(F::from(1 << BITS)) + limb;
+                        let acc_column = self.config.acc[i];
+                        acc_cells.push(region.assign_advice(
+                            || "acc",
+                            acc_column,
+                            row,
+                            || acc[i],
+                        )?);
+                    }
+                }
+
+                region.constrain_equal(acc_cells[0].cell(), a.cell())?;
+                region.constrain_equal(acc_cells[1].cell(), b.cell())?;
+                Ok(acc_cells[2].clone())
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Exposes a op b for every pair, words of LIMBS limbs
+    #[derive(Default)]
+    struct MyCircuit<F, const BITS: usize, const LIMBS: usize> {
+        pairs: Vec<(Value<F>, Value<F>)>,
+        op: Option<BitwiseOp>,
+    }
+
+    impl<F: FieldExt, const BITS: usize, const LIMBS: usize> Circuit<F> for MyCircuit<F, BITS, LIMBS> {
+        type Config = BitwiseConfig<F, BITS>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                pairs: vec![(Value::unknown(), Value::unknown()); self.pairs.len()],
+                op: self.op,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            BitwiseChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BitwiseChip::construct(config);
+            chip.load_table(&mut layouter)?;
+
+            for (row, (a, b)) in self.pairs.iter().enumerate() {
+                let a = chip.load_private(layouter.namespace(|| "a"), *a)?;
+                let b = chip.load_private(layouter.namespace(|| "b"), *b)?;
+                let op = self.op.unwrap();
+                let out = chip.bitwise(layouter.namespace(|| "a op b"), op, &a, &b, LIMBS)?;
+                chip.expose_public(layouter.namespace(|| "out"), &out, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run<const BITS: usize, const LIMBS: usize>(
+        k: u32,
+        op: BitwiseOp,
+        pairs: &[(u64, u64)],
+        out: &[u64],
+    ) -> MockProver<Fp> {
+        let circuit = MyCircuit::<Fp, BITS, LIMBS> {
+            pairs: pairs
+                .iter()
+                .map(|(a, b)| (Value::known(Fp::from(*a)), Value::known(Fp::from(*b))))
+                .collect(),
+            op: Some(op),
+        };
+        let public_input = out.iter().map(|v| Fp::from(*v)).collect();
+        MockProver::run(k, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_bitwise_nibbles_exhaustive() {
+        let pairs: Vec<_> = (0..16).flat_map(|a| (0..16).map(move |b| (a, b))).collect();
+        for op in BitwiseOp::ALL {
+            let out: Vec<_> = pairs.iter().map(|(a, b)| op.apply(*a, *b)).collect();
+            run::<4, 1>(11, op, &pairs, &out).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_bitwise_words() {
+        let pairs = [
+            (0xdead_beef, 0x0123_4567),
+            (0xffff_ffff, 0),
+            (0, 0),
+            (0x8000_0001, 0xffff_ffff),
+        ];
+        for op in BitwiseOp::ALL {
+            let out: Vec<_> = pairs.iter().map(|(a, b)| op.apply(*a, *b)).collect();
+            run::<4, 8>(10, op, &pairs, &out).assert_satisfied();
+        }
+
+        let wrong = run::<4, 8>(10, BitwiseOp::Xor, &[(6, 3)], &[7]);
+        assert!(wrong.verify().is_err());
+
+        // 2^32 does not fit in 8 nibbles
+        let too_big = run::<4, 8>(10, BitwiseOp::And, &[(1 << 32, 1)], &[0]);
+        assert!(too_big.verify().is_err());
+    }
+
+    #[test]
+    fn test_bitwise_bytes() {
+        let pairs = [(0xa5, 0x0f), (0xff, 0xff), (0x12_34, 0xff_00)];
+        for op in BitwiseOp::ALL {
+            let out: Vec<_> = pairs.iter().map(|(a, b)| op.apply(*a, *b)).collect();
+            run::<8, 2>(18, op, &pairs, &out).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_bitwise_bad_width() {
+        fn synthesize<const LIMBS: usize>() -> Result<MockProver<Fp>, Error> {
+            let circuit = MyCircuit::<Fp, 4, LIMBS> {
+                pairs: vec![(Value::known(Fp::one()), Value::known(Fp::one()))],
+                op: Some(BitwiseOp::And),
+            };
+            MockProver::run(10, &circuit, vec![vec![Fp::one()]])
+        }
+
+        // No limbs, and 17 nibbles is more than 64 bits
+        assert!(matches!(synthesize::<0>(), Err(Error::Synthesis)));
+        assert!(matches!(synthesize::<17>(), Err(Error::Synthesis)));
+        synthesize::<16>().unwrap().assert_satisfied();
+    }
+}
@@ -0,0 +1,425 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// Boolean logic on cells. Every row constrains its output to be a bit, and the
+/// binary operations also constrain their inputs:
+///
+///     a | b | out        | selector
+///    ------------------------------------
+///       |   | x          | q_bool         x ∈ {0, 1}
+///     x | y | x·y        | q_and
+///     x | y | x+y-x·y    | q_or
+///     x |   | 1-x        | q_not
+///     x | y | x+y-2·x·y  | q_xor
+///     x | y | 1-x·y      | q_nand
+///     v |   | v == 0     | q_is_zero      via IsZeroChip, inv in `inv`
+///
+/// is_zero turns IsZeroChip's expression into a boolean cell, so comparisons
+/// can be combined with the logic gates.
+#[derive(Debug, Clone)]
+pub struct BoolConfig<F> {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_bool: Selector,
+    pub q_and: Selector,
+    pub q_or: Selector,
+    pub q_not: Selector,
+    pub q_xor: Selector,
+    pub q_nand: Selector,
+    pub q_is_zero: Selector,
+    pub is_zero: IsZeroConfig<F>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BoolChip<F: FieldExt> {
+    config: BoolConfig<F>,
+}
+
+impl<F: FieldExt> BoolChip<F> {
+    pub fn construct(config: BoolConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BoolConfig<F> {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let inv = meta.advice_column();
+        let q_bool = meta.selector();
+        let q_and = meta.selector();
+        let q_or = meta.selector();
+        let q_not = meta.selector();
+        let q_xor = meta.selector();
+        let q_nand = meta.selector();
+        let q_is_zero = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+
+        let one = || Expression::Constant(F::one());
+        let bool_check = move |x: Expression<F>| x.clone() * (one() - x);
+
+        meta.create_gate("bool", |meta| {
+            let s = meta.query_selector(q_bool);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * bool_check(out)]
+        });
+
+        // Binary gates: out = op(a, b) with a, b and out bits
+        let mut binary_gate =
+            |name: &'static str,
+             selector: Selector,
+             op: fn(Expression<F>, Expression<F>) -> Expression<F>| {
+                meta.create_gate(name, |meta| {
+                    let s = meta.query_selector(selector);
+                    let a = meta.query_advice(a, Rotation::cur());
+                    let b = meta.query_advice(b, Rotation::cur());
+                    let out = meta.query_advice(out, Rotation::cur());
+                    vec![
+                        s.clone() * (op(a.clone(), b.clone()) - out.clone()),
+                        s.clone() * bool_check(a),
+                        s.clone() * bool_check(b),
+                        s * bool_check(out),
+                    ]
+                });
+            };
+        binary_gate("and", q_and, |a, b| a * b);
+        binary_gate("or", q_or, |a, b| a.clone() + b.clone() - a * b);
+        binary_gate("xor", q_xor, |a, b| {
+            a.clone() + b.clone() - Expression::Constant(F::from(2)) * a * b
+        });
+        binary_gate("nand", q_nand, |a, b| {
+            Expression::Constant(F::one()) - a * b
+        });
+
+        meta.create_gate("not", |meta| {
+            let s = meta.query_selector(q_not);
+            let a = meta.query_advice(a, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s.clone() * (one() - a.clone() - out), s * bool_check(a)]
+        });
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_is_zero),
+            |meta| meta.query_advice(a, Rotation::cur()),
+            inv,
+        );
+
+        meta.create_gate("is zero", |meta| {
+            let s = meta.query_selector(q_is_zero);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (out - is_zero.expr())]
+        });
+
+        BoolConfig {
+            a,
+            b,
+            out,
+            q_bool,
+            q_and,
+            q_or,
+            q_not,
+            q_xor,
+            q_nand,
+            q_is_zero,
+            is_zero,
+            instance,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    pub fn assert_bool(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assert bool",
+            |mut region| {
+                self.config.q_bool.enable(&mut region, 0)?;
+                x.copy_advice(|| "x", &mut region, self.config.out, 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "and", self.config.q_and, a, b, |a, b| a & b)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "or", self.config.q_or, a, b, |a, b| a | b)
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "xor", self.config.q_xor, a, b, |a, b| a ^ b)
+    }
+
+    pub fn nand(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "nand", self.config.q_nand, a, b, |a, b| !(a & b))
+    }
+
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "not",
+            |mut region| {
+                self.config.q_not.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let out = a.value().map(|a| F::one() - a);
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    /// Returns the cell holding value == 0.
+    pub fn is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero = IsZeroChip::construct(self.config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                self.config.q_is_zero.enable(&mut region, 0)?;
+                let value = value.copy_advice(|| "value", &mut region, self.config.a, 0)?;
+                is_zero.assign(&mut region, 0, value.value().copied())?;
+
+                let out = value.value().map(|v| F::from((*v == F::zero()) as u64));
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    fn binary(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: fn(bool, bool) -> bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+
+                // Non-bit inputs leave the row unsatisfied
+                let bit = |x: &F| *x == F::one();
+                let out = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| F::from(op(bit(a), bit(b)) as u64));
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_equal::{IsEqualChip, IsEqualConfig};
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Exposes x and y, x AND y, x OR y, NOT x, x XOR y and x NAND y
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        x: Value<F>,
+        y: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = BoolConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            BoolChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BoolChip::construct(config);
+
+            let x = chip.load_private(layouter.namespace(|| "x"), self.x)?;
+            let y = chip.load_private(layouter.namespace(|| "y"), self.y)?;
+            chip.assert_bool(layouter.namespace(|| "x is a bit"), &x)?;
+            chip.assert_bool(layouter.namespace(|| "y is a bit"), &y)?;
+
+            let outputs = [
+                chip.and(layouter.namespace(|| "and"), &x, &y)?,
+                chip.or(layouter.namespace(|| "or"), &x, &y)?,
+                chip.not(layouter.namespace(|| "not"), &x)?,
+                chip.xor(layouter.namespace(|| "xor"), &x, &y)?,
+                chip.nand(layouter.namespace(|| "nand"), &x, &y)?,
+            ];
+            for (row, out) in outputs.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), out, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    // a == b && c != 0
+    #[derive(Default)]
+    struct ConditionCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ConditionCircuit<F> {
+        type Config = (BoolConfig<F>, IsEqualConfig<F>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            (BoolChip::configure(meta), IsEqualChip::configure(meta))
+        }
+
+        fn synthesize(
+            &self,
+            (config, is_equal): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BoolChip::construct(config);
+            let is_equal = IsEqualChip::construct(is_equal);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            let c = chip.load_private(layouter.namespace(|| "c"), self.c)?;
+
+            let a_eq_b = is_equal.is_equal(layouter.namespace(|| "a == b"), &a, &b)?;
+            let c_is_zero = chip.is_zero(layouter.namespace(|| "c == 0"), &c)?;
+            let c_nonzero = chip.not(layouter.namespace(|| "c != 0"), &c_is_zero)?;
+            let out = chip.and(layouter.namespace(|| "&&"), &a_eq_b, &c_nonzero)?;
+
+            chip.expose_public(layouter.namespace(|| "out"), &out, 0)
+        }
+    }
+
+    #[test]
+    fn test_bool_gates() {
+        for x in [false, true] {
+            for y in [false, true] {
+                let circuit = MyCircuit {
+                    x: Value::known(Fp::from(x as u64)),
+                    y: Value::known(Fp::from(y as u64)),
+                };
+                let outputs = [x & y, x | y, !x, x ^ y, !(x & y)];
+                let public_input: Vec<_> = outputs.iter().map(|b| Fp::from(*b as u64)).collect();
+
+                let prover = MockProver::run(4, &circuit, vec![public_input.clone()]).unwrap();
+                prover.assert_satisfied();
+
+                // Flip each output
+                for i in 0..outputs.len() {
+                    let mut public_input = public_input.clone();
+                    public_input[i] = Fp::one() - public_input[i];
+                    let prover = MockProver::run(4, &circuit, vec![public_input]).unwrap();
+                    assert!(prover.verify().is_err());
+                }
+            }
+        }
+
+        // x = 2 is not a bit
+        let circuit = MyCircuit {
+            x: Value::known(Fp::from(2)),
+            y: Value::known(Fp::zero()),
+        };
+        let public_input = [0, 0, 0, 0, 1].iter().map(|v| Fp::from(*v)).collect();
+        let prover = MockProver::run(4, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_bool_condition() {
+        let run = |a: u64, b: u64, c: u64, out: u64| {
+            let circuit = ConditionCircuit {
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+                c: Value::known(Fp::from(c)),
+            };
+            // IsEqualConfig's instance column is unused
+            MockProver::run(4, &circuit, vec![vec![Fp::from(out)], vec![]]).unwrap()
+        };
+
+        run(5, 5, 7, 1).assert_satisfied();
+        run(5, 5, 0, 0).assert_satisfied();
+        run(5, 6, 7, 0).assert_satisfied();
+        run(5, 6, 0, 0).assert_satisfied();
+
+        assert!(run(5, 5, 0, 1).verify().is_err());
+        assert!(run(5, 6, 7, 1).verify().is_err());
+    }
+}
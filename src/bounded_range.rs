@@ -0,0 +1,373 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::running_sum::RunningSumConfig;
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Proves lo <= v < hi for bounds that need not be powers of two, and may be
+/// cells assigned at runtime, by range checking both differences:
+///
+///     v - lo          in 0..2^num_bits
+///     hi - 1 - v      in 0..2^num_bits
+///
+/// Together they give lo <= v <= hi - 1. The range checks are lookups into
+/// the shared RangeTableConfig, a single window each when num_bits is the
+/// table's K. A value below lo or at hi or above makes one of the differences
+/// wrap around the field. Bounds with hi <= lo cannot be met, since the two
+/// differences would have to add up to hi - 1 - lo.
+///
+/// num_bits only needs 2^num_bits >= hi - lo for every v in range to pass,
+/// and 2^(num_bits+1) below the field modulus. Runtime bounds are range
+/// checked to 0..2^num_bits as well: otherwise lo = p - 5 would accept v = 2,
+/// since v - lo = 7.
+///
+///     v | lo | hi | v - lo | hi - 1 - v | q_bounds
+///    ----------------------------------------------
+///     v | lo | hi | d_lo   | d_hi       |    1
+#[derive(Debug, Clone)]
+pub(super) struct BoundedRangeConfig<F: FieldExt, const LOOKUP_RANGE: usize> {
+    v: Column<Advice>,
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    d_lo: Column<Advice>,
+    d_hi: Column<Advice>,
+    q_bounds: Selector,
+    instance: Column<Instance>,
+    running_sum: RunningSumConfig<F, LOOKUP_RANGE>,
+}
+
+impl<F: FieldExt, const LOOKUP_RANGE: usize> BoundedRangeConfig<F, LOOKUP_RANGE> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> Self {
+        let v = meta.advice_column();
+        let lo = meta.advice_column();
+        let hi = meta.advice_column();
+        let d_lo = meta.advice_column();
+        let d_hi = meta.advice_column();
+        let q_bounds = meta.selector();
+        let instance = meta.instance_column();
+        let running_sum = RunningSumConfig::configure(meta, table);
+
+        for column in [v, lo, hi, d_lo, d_hi] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("bound differences", |meta| {
+            let s = meta.query_selector(q_bounds);
+            let v = meta.query_advice(v, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let d_lo = meta.query_advice(d_lo, Rotation::cur());
+            let d_hi = meta.query_advice(d_hi, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                s.clone() * (v.clone() - lo - d_lo),
+                s * (hi - one - v - d_hi),
+            ]
+        });
+
+        Self {
+            v,
+            lo,
+            hi,
+            d_lo,
+            d_hi,
+            q_bounds,
+            instance,
+            running_sum,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.v, 0, || value),
+        )
+    }
+
+    pub fn load_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load public",
+            |mut region| {
+                region.assign_advice_from_instance(|| "public input", self.instance, row, self.v, 0)
+            },
+        )
+    }
+
+    /// lo <= v < hi with bounds fixed in the circuit.
+    pub fn assert_in_const_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        v: &AssignedCell<F, F>,
+        lo: u64,
+        hi: u64,
+    ) -> Result<(), Error> {
+        assert!(lo < hi);
+        // Smallest num_bits with 2^num_bits >= hi - lo
+        let num_bits = (64 - (hi - lo - 1).leading_zeros() as usize).max(1);
+
+        let [lo, hi] = layouter.assign_region(
+            || "constant bounds",
+            |mut region| {
+                Ok([
+                    region.assign_advice_from_constant(|| "lo", self.lo, 0, F::from(lo))?,
+                    region.assign_advice_from_constant(|| "hi", self.hi, 0, F::from(hi))?,
+                ])
+            },
+        )?;
+
+        // Constants are known to be in range
+        self.assert_differences(layouter, tables, v, &lo, &hi, num_bits)
+    }
+
+    /// lo <= v < hi with bounds assigned at runtime, e.g. from advice or
+    /// instance cells. lo and hi are range checked to 0..2^num_bits.
+    pub fn assert_in_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        v: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        hi: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        self.running_sum
+            .copy_check(layouter.namespace(|| "lo in range"), tables, lo, num_bits)?;
+        self.running_sum
+            .copy_check(layouter.namespace(|| "hi in range"), tables, hi, num_bits)?;
+        self.assert_differences(layouter, tables, v, lo, hi, num_bits)
+    }
+
+    fn assert_differences(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        v: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        hi: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let [d_lo, d_hi] = layouter.assign_region(
+            || "bound differences",
+            |mut region| {
+                self.q_bounds.enable(&mut region, 0)?;
+                let v = v.copy_advice(|| "v", &mut region, self.v, 0)?;
+                let lo = lo.copy_advice(|| "lo", &mut region, self.lo, 0)?;
+                let hi = hi.copy_advice(|| "hi", &mut region, self.hi, 0)?;
+
+                let d_lo = v.value().copied() - lo.value();
+                let d_hi = hi.value().copied() - Value::known(F::one()) - v.value();
+                Ok([
+                    region.assign_advice(|| "v - lo", self.d_lo, 0, || d_lo)?,
+                    region.assign_advice(|| "hi - 1 - v", self.d_hi, 0, || d_hi)?,
+                ])
+            },
+        )?;
+
+        self.running_sum
+            .copy_check(layouter.namespace(|| "v >= lo"), tables, &d_lo, num_bits)?;
+        self.running_sum
+            .copy_check(layouter.namespace(|| "v < hi"), tables, &d_hi, num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Checks lo <= v < hi twice: with lo and hi as constants, and with lo
+    // private and hi public at instance row 0
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        v: Value<F>,
+        lo: u64,
+        hi: u64,
+        num_bits: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (BoundedRangeConfig<F, 256>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                v: Value::unknown(),
+                ..*self
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (BoundedRangeConfig::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+
+            let v = config.load_private(layouter.namespace(|| "v"), self.v)?;
+            config.assert_in_const_range(
+                layouter.namespace(|| "constant bounds"),
+                &tables,
+                &v,
+                self.lo,
+                self.hi,
+            )?;
+
+            let lo = Value::known(F::from(self.lo));
+            let lo = config.load_private(layouter.namespace(|| "lo"), lo)?;
+            let hi = config.load_public(layouter.namespace(|| "hi"), 0)?;
+            config.assert_in_range(
+                layouter.namespace(|| "runtime bounds"),
+                &tables,
+                &v,
+                &lo,
+                &hi,
+                self.num_bits,
+            )
+        }
+    }
+
+    fn run(v: u64, lo: u64, hi: u64, num_bits: usize) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            v: Value::known(Fp::from(v)),
+            lo,
+            hi,
+            num_bits,
+        };
+        MockProver::run(9, &circuit, vec![vec![Fp::from(hi)]]).unwrap()
+    }
+
+    #[test]
+    fn test_bounded_range() {
+        // Non-power-of-two bounds, within one 8-bit window
+        for v in [10, 11, 100, 209] {
+            run(v, 10, 210, 8).assert_satisfied();
+        }
+        for v in [0, 9, 210, 211, 1000] {
+            assert!(run(v, 10, 210, 8).verify().is_err());
+        }
+
+        // A single value
+        run(7, 7, 8, 4).assert_satisfied();
+        assert!(run(8, 7, 8, 4).verify().is_err());
+
+        // Wider than the table, and a partial window
+        run(999_999, 1000, 1_000_000, 20).assert_satisfied();
+        assert!(run(1_000_000, 1000, 1_000_000, 20).verify().is_err());
+        assert!(run(999, 1000, 1_000_000, 20).verify().is_err());
+
+        // 64-bit bounds
+        let (lo, hi) = (1 << 63, u64::MAX);
+        run(lo, lo, hi, 64).assert_satisfied();
+        run(hi - 1, lo, hi, 64).assert_satisfied();
+        assert!(run(hi, lo, hi, 64).verify().is_err());
+        assert!(run(lo - 1, lo, hi, 64).verify().is_err());
+
+        // Runtime bounds must fit in num_bits too
+        assert!(run(7, 7, 8, 3).verify().is_err());
+        assert!(run(100, 10, 210, 7).verify().is_err());
+    }
+
+    #[test]
+    fn test_bounded_range_runtime() {
+        // hi is public, so the same circuit checks against another bound
+        let circuit = MyCircuit {
+            v: Value::known(Fp::from(150)),
+            lo: 10,
+            hi: 210,
+            num_bits: 8,
+        };
+        let run = |hi: u64| MockProver::run(9, &circuit, vec![vec![Fp::from(hi)]]).unwrap();
+
+        run(210).assert_satisfied();
+        run(151).assert_satisfied();
+        assert!(run(150).verify().is_err());
+
+        // hi <= lo has no v at all
+        assert!(run(10).verify().is_err());
+        assert!(run(5).verify().is_err());
+    }
+
+    // Only the runtime check, with a lo given as any field element
+    #[derive(Default)]
+    struct WrappedCircuit<F> {
+        v: Value<F>,
+        lo: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for WrappedCircuit<F> {
+        type Config = (BoundedRangeConfig<F, 256>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            MyCircuit::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+
+            let v = config.load_private(layouter.namespace(|| "v"), self.v)?;
+            let lo = config.load_private(layouter.namespace(|| "lo"), self.lo)?;
+            let hi = config.load_public(layouter.namespace(|| "hi"), 0)?;
+            config.assert_in_range(layouter.namespace(|| "bounds"), &tables, &v, &lo, &hi, 8)
+        }
+    }
+
+    #[test]
+    fn test_bounded_range_wrapped_bound() {
+        let run = |lo: Fp, hi: Fp| {
+            let circuit = WrappedCircuit {
+                v: Value::known(Fp::from(2)),
+                lo: Value::known(lo),
+            };
+            MockProver::run(9, &circuit, vec![vec![hi]]).unwrap()
+        };
+
+        run(Fp::zero(), Fp::from(10)).assert_satisfied();
+
+        // v - lo = 7 and hi - 1 - v = 7 are both in range, but lo = p - 5 is
+        // not
+        assert!(run(-Fp::from(5), Fp::from(10)).verify().is_err());
+
+        // hi = p - 1 would put v = p - 3 in range
+        let circuit = WrappedCircuit {
+            v: Value::known(-Fp::from(3)),
+            lo: Value::known(-Fp::from(5)),
+        };
+        let prover = MockProver::run(9, &circuit, vec![vec![-Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
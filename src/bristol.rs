@@ -0,0 +1,762 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+    },
+    poly::Rotation,
+};
+
+/// Boolean circuits in Bristol Fashion:
+///
+///     <num_gates> <num_wires>
+///     <num_inputs> <input_1 bits> ... <input_n bits>
+///     <num_outputs> <output_1 bits> ... <output_m bits>
+///
+///     2 1 <a> <b> <out> AND
+///     2 1 <a> <b> <out> XOR
+///     1 1 <a> <out> INV
+///     1 1 <a> <out> EQW        (out = a)
+///     1 1 <0|1> <out> EQ       (out = constant)
+///
+/// The inputs are the first wires and the outputs the last wires, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    And(usize, usize, usize),
+    Xor(usize, usize, usize),
+    Inv(usize, usize),
+    Eqw(usize, usize),
+    Eq(bool, usize),
+}
+
+impl Gate {
+    fn output(&self) -> usize {
+        match *self {
+            Self::And(_, _, out) | Self::Xor(_, _, out) => out,
+            Self::Inv(_, out) | Self::Eqw(_, out) | Self::Eq(_, out) => out,
+        }
+    }
+
+    fn inputs(&self) -> Vec<usize> {
+        match *self {
+            Self::And(a, b, _) | Self::Xor(a, b, _) => vec![a, b],
+            Self::Inv(a, _) | Self::Eqw(a, _) => vec![a],
+            Self::Eq(_, _) => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BristolError {
+    UnexpectedEnd,
+    InvalidHeader(usize),
+    InvalidNumber(String),
+    UnknownGate(String),
+    InvalidGate(usize),
+    InvalidWire(usize),
+    UndefinedWire(usize),
+    WireReassigned(usize),
+    WrongInputs,
+    WrongPublicFlags,
+}
+
+impl fmt::Display for BristolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of circuit"),
+            Self::InvalidHeader(line) => write!(f, "malformed header line {}", line),
+            Self::InvalidNumber(s) => write!(f, "invalid number {:?}", s),
+            Self::UnknownGate(s) => write!(f, "unknown gate {:?}", s),
+            Self::InvalidGate(i) => write!(f, "malformed gate {}", i),
+            Self::InvalidWire(w) => write!(f, "wire {} is out of range", w),
+            Self::UndefinedWire(w) => write!(f, "wire {} is read before it is assigned", w),
+            Self::WireReassigned(w) => write!(f, "wire {} is assigned twice", w),
+            Self::WrongInputs => write!(f, "inputs do not match the circuit"),
+            Self::WrongPublicFlags => write!(f, "public flags do not match the circuit"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bristol {
+    pub num_wires: usize,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub gates: Vec<Gate>,
+}
+
+impl Bristol {
+    pub fn parse(src: &str) -> Result<Self, BristolError> {
+        let mut lines = src.lines().map(str::trim).filter(|line| !line.is_empty());
+        let mut header = || -> Result<Vec<usize>, BristolError> {
+            let line = lines.next().ok_or(BristolError::UnexpectedEnd)?;
+            line.split_whitespace().map(number).collect()
+        };
+
+        let counts = header()?;
+        let (num_gates, num_wires) = match counts[..] {
+            [num_gates, num_wires] => (num_gates, num_wires),
+            _ => return Err(BristolError::InvalidHeader(0)),
+        };
+        let inputs = header()?;
+        let outputs = header()?;
+        if inputs.is_empty() || inputs[0] != inputs.len() - 1 {
+            return Err(BristolError::InvalidHeader(1));
+        }
+        if outputs.is_empty() || outputs[0] != outputs.len() - 1 {
+            return Err(BristolError::InvalidHeader(2));
+        }
+
+        let gates = (0..num_gates)
+            .map(|i| {
+                let line = lines.next().ok_or(BristolError::UnexpectedEnd)?;
+                let tokens: Vec<_> = line.split_whitespace().collect();
+                let (op, args) = tokens.split_last().ok_or(BristolError::InvalidGate(i))?;
+                let args = args
+                    .iter()
+                    .copied()
+                    .map(number)
+                    .collect::<Result<Vec<_>, _>>()?;
+                match (*op, &args[..]) {
+                    ("AND", [2, 1, a, b, out]) => Ok(Gate::And(*a, *b, *out)),
+                    ("XOR", [2, 1, a, b, out]) => Ok(Gate::Xor(*a, *b, *out)),
+                    ("INV", [1, 1, a, out]) => Ok(Gate::Inv(*a, *out)),
+                    ("EQW", [1, 1, a, out]) => Ok(Gate::Eqw(*a, *out)),
+                    ("EQ", [1, 1, k, out]) if *k < 2 => Ok(Gate::Eq(*k == 1, *out)),
+                    ("AND" | "XOR" | "INV" | "EQW" | "EQ", _) => Err(BristolError::InvalidGate(i)),
+                    _ => Err(BristolError::UnknownGate(op.to_string())),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        let circuit = Self {
+            num_wires,
+            inputs: inputs[1..].to_vec(),
+            outputs: outputs[1..].to_vec(),
+            gates,
+        };
+        circuit.check()?;
+        Ok(circuit)
+    }
+
+    // Every wire is assigned once, before it is read.
+    fn check(&self) -> Result<(), BristolError> {
+        let num_inputs: usize = self.inputs.iter().sum();
+        let num_outputs: usize = self.outputs.iter().sum();
+        if num_inputs + num_outputs > self.num_wires {
+            return Err(BristolError::InvalidWire(num_inputs + num_outputs));
+        }
+
+        let mut defined = vec![false; self.num_wires];
+        defined[..num_inputs].iter_mut().for_each(|d| *d = true);
+        for gate in self.gates.iter() {
+            for wire in gate.inputs() {
+                if !*defined.get(wire).ok_or(BristolError::InvalidWire(wire))? {
+                    return Err(BristolError::UndefinedWire(wire));
+                }
+            }
+            let out = gate.output();
+            match defined.get_mut(out) {
+                None => return Err(BristolError::InvalidWire(out)),
+                Some(true) => return Err(BristolError::WireReassigned(out)),
+                Some(d) => *d = true,
+            }
+        }
+
+        match (self.num_wires - num_outputs..self.num_wires).find(|w| !defined[*w]) {
+            Some(wire) => Err(BristolError::UndefinedWire(wire)),
+            None => Ok(()),
+        }
+    }
+
+    /// Evaluates every wire. Unused wires are false.
+    pub fn evaluate(&self, inputs: &[Vec<bool>]) -> Result<Vec<bool>, BristolError> {
+        let sizes: Vec<_> = inputs.iter().map(|input| input.len()).collect();
+        if sizes != self.inputs {
+            return Err(BristolError::WrongInputs);
+        }
+
+        let mut wires = inputs.concat();
+        wires.resize(self.num_wires, false);
+        for gate in self.gates.iter() {
+            wires[gate.output()] = match *gate {
+                Gate::And(a, b, _) => wires[a] & wires[b],
+                Gate::Xor(a, b, _) => wires[a] ^ wires[b],
+                Gate::Inv(a, _) => !wires[a],
+                Gate::Eqw(a, _) => wires[a],
+                Gate::Eq(k, _) => k,
+            };
+        }
+        Ok(wires)
+    }
+
+    /// Splits the output wires into the declared outputs.
+    pub fn outputs(&self, wires: &[bool]) -> Vec<Vec<bool>> {
+        let mut start = self.num_wires - self.outputs.iter().sum::<usize>();
+        self.outputs
+            .iter()
+            .map(|size| {
+                start += size;
+                wires[start - size..start].to_vec()
+            })
+            .collect()
+    }
+}
+
+fn number(s: &str) -> Result<usize, BristolError> {
+    s.parse()
+        .map_err(|_| BristolError::InvalidNumber(s.to_string()))
+}
+
+/// One row per input bit and per gate, with the gate's output in `out`:
+///
+///     a | b | out     | k | q_input | q_and | q_xor | q_inv | q_eqw | q_eq
+///    ----------------------------------------------------------------------
+///       |   |   x     |   |    1    |   0   |   0   |   0   |   0   |  0
+///     x | y | x·y     |   |    0    |   1   |   0   |   0   |   0   |  0
+///     x | y | x⊕y     |   |    0    |   0   |   1   |   0   |   0   |  0
+///     x |   | 1-x     |   |    0    |   0   |   0   |   1   |   0   |  0
+///     x |   |   x     |   |    0    |   0   |   0   |   0   |   1   |  0
+///       |   |   k     | k |    0    |   0   |   0   |   0   |   0   |  1
+///
+/// Every gate also constrains `out` to be boolean. The gate inputs are copies of
+/// earlier `out` cells, so every wire is a boolean-constrained advice cell.
+#[derive(Debug, Clone)]
+struct BristolConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub k: Column<Fixed>,
+    pub q_input: Selector,
+    pub q_and: Selector,
+    pub q_xor: Selector,
+    pub q_inv: Selector,
+    pub q_eqw: Selector,
+    pub q_eq: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct BristolChip<F: FieldExt> {
+    config: BristolConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BristolChip<F> {
+    pub fn construct(config: BristolConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BristolConfig {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let k = meta.fixed_column();
+        let q_input = meta.selector();
+        let q_and = meta.selector();
+        let q_xor = meta.selector();
+        let q_inv = meta.selector();
+        let q_eqw = meta.selector();
+        let q_eq = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+
+        let bool_check = |out: Expression<F>| out.clone() * (Expression::Constant(F::one()) - out);
+
+        meta.create_gate("input", |meta| {
+            let s = meta.query_selector(q_input);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * bool_check(out)]
+        });
+
+        meta.create_gate("AND", |meta| {
+            let s = meta.query_selector(q_and);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s.clone() * (a * b - out.clone()), s * bool_check(out)]
+        });
+
+        meta.create_gate("XOR", |meta| {
+            let s = meta.query_selector(q_xor);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let two = Expression::Constant(F::from(2));
+            let xor = a.clone() + b.clone() - two * a * b;
+            vec![s.clone() * (xor - out.clone()), s * bool_check(out)]
+        });
+
+        meta.create_gate("INV", |meta| {
+            let s = meta.query_selector(q_inv);
+            let a = meta.query_advice(a, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![s.clone() * (one - a - out.clone()), s * bool_check(out)]
+        });
+
+        meta.create_gate("EQW", |meta| {
+            let s = meta.query_selector(q_eqw);
+            let a = meta.query_advice(a, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s.clone() * (a - out.clone()), s * bool_check(out)]
+        });
+
+        meta.create_gate("EQ", |meta| {
+            let s = meta.query_selector(q_eq);
+            let k = meta.query_fixed(k, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s.clone() * (k - out.clone()), s * bool_check(out)]
+        });
+
+        BristolConfig {
+            a,
+            b,
+            out,
+            k,
+            q_input,
+            q_and,
+            q_xor,
+            q_inv,
+            q_eqw,
+            q_eq,
+            instance,
+        }
+    }
+
+    /// Assigns the inputs and every gate, and returns the output wires.
+    ///
+    /// Public inputs are read from the instance column starting at row 0, in
+    /// order; `inputs` only needs values for the private ones. `public_inputs`
+    /// has one flag per input of the circuit.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        circuit: &Bristol,
+        inputs: &[Vec<Value<bool>>],
+        public_inputs: &[bool],
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        if public_inputs.len() != circuit.inputs.len() {
+            return Err(Error::Synthesis);
+        }
+        let bit = |b: Value<bool>| b.map(|b| F::from(b as u64));
+
+        let wires = layouter.assign_region(
+            || "bristol",
+            |mut region| {
+                let mut wires: Vec<Option<AssignedCell<F, F>>> = vec![None; circuit.num_wires];
+                let mut row = 0;
+                let mut instance_row = 0;
+
+                // Inputs
+                let mut wire = 0;
+                for (i, size) in circuit.inputs.iter().enumerate() {
+                    for j in 0..*size {
+                        self.config.q_input.enable(&mut region, row)?;
+                        let cell = if public_inputs[i] {
+                            instance_row += 1;
+                            region.assign_advice_from_instance(
+                                || "public input",
+                                self.config.instance,
+                                instance_row - 1,
+                                self.config.out,
+                                row,
+                            )?
+                        } else {
+                            let value = inputs
+                                .get(i)
+                                .and_then(|input| input.get(j))
+                                .copied()
+                                .unwrap_or_else(Value::unknown);
+                            region.assign_advice(
+                                || "private input",
+                                self.config.out,
+                                row,
+                                || bit(value),
+                            )?
+                        };
+                        wires[wire] = Some(cell);
+                        wire += 1;
+                        row += 1;
+                    }
+                }
+
+                // Gates, in order
+                for gate in circuit.gates.iter() {
+                    let input = |wire: usize| wires[wire].clone().unwrap();
+                    let value = |wire: usize| input(wire).value().map(|v| *v == F::one());
+
+                    let (selector, out) = match *gate {
+                        Gate::And(a, b, _) => {
+                            input(a).copy_advice(|| "a", &mut region, self.config.a, row)?;
+                            input(b).copy_advice(|| "b", &mut region, self.config.b, row)?;
+                            (
+                                self.config.q_and,
+                                value(a).zip(value(b)).map(|(a, b)| a & b),
+                            )
+                        }
+                        Gate::Xor(a, b, _) => {
+                            input(a).copy_advice(|| "a", &mut region, self.config.a, row)?;
+                            input(b).copy_advice(|| "b", &mut region, self.config.b, row)?;
+                            (
+                                self.config.q_xor,
+                                value(a).zip(value(b)).map(|(a, b)| a ^ b),
+                            )
+                        }
+                        Gate::Inv(a, _) => {
+                            input(a).copy_advice(|| "a", &mut region, self.config.a, row)?;
+                            (self.config.q_inv, value(a).map(|a| !a))
+                        }
+                        Gate::Eqw(a, _) => {
+                            input(a).copy_advice(|| "a", &mut region, self.config.a, row)?;
+                            (self.config.q_eqw, value(a))
+                        }
+                        Gate::Eq(k, _) => {
+                            region.assign_fixed(
+                                || "k",
+                                self.config.k,
+                                row,
+                                || Value::known(F::from(k as u64)),
+                            )?;
+                            (self.config.q_eq, Value::known(k))
+                        }
+                    };
+
+                    selector.enable(&mut region, row)?;
+                    let cell = region.assign_advice(|| "out", self.config.out, row, || bit(out))?;
+                    wires[gate.output()] = Some(cell);
+                    row += 1;
+                }
+
+                Ok(wires)
+            },
+        )?;
+
+        let mut start = circuit.num_wires - circuit.outputs.iter().sum::<usize>();
+        Ok(circuit
+            .outputs
+            .iter()
+            .map(|size| {
+                start += size;
+                wires[start - size..start]
+                    .iter()
+                    .map(|w| w.clone().unwrap())
+                    .collect()
+            })
+            .collect())
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Proves an evaluation of a Bristol circuit. The instance column holds the
+/// public input bits followed by the public output bits.
+#[derive(Debug, Clone, Default)]
+struct BristolCircuit {
+    circuit: Bristol,
+    inputs: Vec<Vec<Value<bool>>>,
+    public_inputs: Vec<bool>,
+    public_outputs: Vec<bool>,
+}
+
+impl BristolCircuit {
+    /// `public_inputs` and `public_outputs` flag each input and output of the
+    /// circuit as public or private.
+    pub fn new(
+        circuit: Bristol,
+        inputs: &[Vec<bool>],
+        public_inputs: Vec<bool>,
+        public_outputs: Vec<bool>,
+    ) -> Result<Self, BristolError> {
+        if public_inputs.len() != circuit.inputs.len()
+            || public_outputs.len() != circuit.outputs.len()
+        {
+            return Err(BristolError::WrongPublicFlags);
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|input| input.iter().map(|b| Value::known(*b)).collect())
+            .collect();
+        Ok(Self {
+            circuit,
+            inputs,
+            public_inputs,
+            public_outputs,
+        })
+    }
+
+    /// The instance column for the given inputs.
+    pub fn public_signals<F: FieldExt>(
+        &self,
+        inputs: &[Vec<bool>],
+    ) -> Result<Vec<F>, BristolError> {
+        let wires = self.circuit.evaluate(inputs)?;
+        let outputs = self.circuit.outputs(&wires);
+
+        let public_inputs = inputs.iter().zip(self.public_inputs.iter());
+        let public_outputs = outputs.iter().zip(self.public_outputs.iter());
+        Ok(public_inputs
+            .chain(public_outputs)
+            .filter(|(_, public)| **public)
+            .flat_map(|(bits, _)| bits.iter().map(|b| F::from(*b as u64)))
+            .collect())
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for BristolCircuit {
+    type Config = BristolConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.clone(),
+            inputs: vec![],
+            public_inputs: self.public_inputs.clone(),
+            public_outputs: self.public_outputs.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BristolChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BristolChip::construct(config);
+
+        let outputs = chip.assign(
+            layouter.namespace(|| "evaluate"),
+            &self.circuit,
+            &self.inputs,
+            &self.public_inputs,
+        )?;
+
+        let mut row = self
+            .circuit
+            .inputs
+            .iter()
+            .zip(self.public_inputs.iter())
+            .filter(|(_, public)| **public)
+            .map(|(size, _)| size)
+            .sum();
+        for (output, public) in outputs.iter().zip(self.public_outputs.iter()) {
+            if !public {
+                continue;
+            }
+            for bit in output.iter() {
+                chip.expose_public(layouter.namespace(|| "output"), bit, row)?;
+                row += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    // Ripple-carry adder, least significant bit first. Generated locally, so
+    // not the published Bristol Fashion adder64 (376 gates, 504 wires)
+    const RIPPLE_ADDER64: &str = include_str!("../fixtures/bristol/ripple_adder64.txt");
+
+    // The published adder64 from https://homes.esat.kuleuven.be/~nsmart/MPC/
+    const ADDER64_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/bristol/adder64.txt");
+
+    fn to_bits(v: u64) -> Vec<bool> {
+        (0..64).map(|i| (v >> i) & 1 == 1).collect()
+    }
+
+    fn from_bits(bits: &[bool]) -> u64 {
+        bits.iter().rev().fold(0, |acc, b| (acc << 1) | *b as u64)
+    }
+
+    #[test]
+    fn test_parse() {
+        let adder = Bristol::parse(RIPPLE_ADDER64).unwrap();
+        assert_eq!(adder.num_wires, 442);
+        assert_eq!(adder.inputs, vec![64, 64]);
+        assert_eq!(adder.outputs, vec![64]);
+        assert_eq!(adder.gates.len(), 314);
+
+        for (x, y) in [
+            (1, 2),
+            (u64::MAX, 1),
+            (0xdead_beef_0000_0001, 0x1234_5678_9abc_def0),
+        ] {
+            let wires = adder.evaluate(&[to_bits(x), to_bits(y)]).unwrap();
+            assert_eq!(from_bits(&adder.outputs(&wires)[0]), x.wrapping_add(y));
+        }
+
+        let parse = |src: &str| Bristol::parse(src).unwrap_err();
+        assert_eq!(
+            parse("1 3\n1 2\n1 1\n\n2 1 0 1 2 OR"),
+            BristolError::UnknownGate("OR".into())
+        );
+        assert_eq!(
+            parse("1 3\n1 2\n1 1\n\n2 1 0 3 2 AND"),
+            BristolError::InvalidWire(3)
+        );
+        assert_eq!(
+            parse("1 3\n1 1\n1 1\n\n2 1 0 1 2 AND"),
+            BristolError::UndefinedWire(1)
+        );
+        assert_eq!(
+            parse("1 3\n1 2\n1 1\n\n2 1 0 1 1 AND"),
+            BristolError::WireReassigned(1)
+        );
+        assert_eq!(
+            parse("1 3\n1 2\n1 1\n\n1 1 2 2 EQ"),
+            BristolError::InvalidGate(0)
+        );
+        assert_eq!(
+            parse("2 3\n1 2\n1 1\n\n2 1 0 1 2 AND"),
+            BristolError::UnexpectedEnd
+        );
+        assert_eq!(
+            parse("1 3 4\n1 2\n1 1\n\n2 1 0 1 2 AND"),
+            BristolError::InvalidHeader(0)
+        );
+        assert_eq!(
+            parse("1 3\n2 2\n1 1\n\n2 1 0 1 2 AND"),
+            BristolError::InvalidHeader(1)
+        );
+        assert_eq!(
+            parse("1 3\n1 2\n1 1 1\n\n2 1 0 1 2 AND"),
+            BristolError::InvalidHeader(2)
+        );
+
+        // One public flag per input and output
+        let circuit = Bristol::parse("1 3\n1 2\n1 1\n\n2 1 0 1 2 AND").unwrap();
+        assert_eq!(
+            BristolCircuit::new(circuit.clone(), &[], vec![], vec![true]).unwrap_err(),
+            BristolError::WrongPublicFlags
+        );
+        assert_eq!(
+            BristolCircuit::new(circuit, &[], vec![false], vec![]).unwrap_err(),
+            BristolError::WrongPublicFlags
+        );
+    }
+
+    #[test]
+    fn test_ripple_adder64() {
+        check_adder64(&Bristol::parse(RIPPLE_ADDER64).unwrap());
+    }
+
+    #[test]
+    #[ignore = "needs the published adder64.txt in fixtures/bristol"]
+    fn test_adder64() {
+        let src = std::fs::read_to_string(ADDER64_PATH).unwrap();
+        let adder = Bristol::parse(&src).unwrap();
+        assert_eq!(adder.num_wires, 504);
+        assert_eq!(adder.inputs, vec![64, 64]);
+        assert_eq!(adder.outputs, vec![64]);
+        assert_eq!(adder.gates.len(), 376);
+
+        check_adder64(&adder);
+    }
+
+    // Proves x + y for a 64-bit adder with x private, y and the sum public
+    fn check_adder64(adder: &Bristol) {
+        let circuit = |x: u64, y: u64| {
+            let inputs = [to_bits(x), to_bits(y)];
+            let circuit =
+                BristolCircuit::new(adder.clone(), &inputs, vec![false, true], vec![true]).unwrap();
+            let public_input = circuit.public_signals::<Fp>(&inputs).unwrap();
+            (circuit, public_input)
+        };
+
+        for (x, y) in [
+            (0, 0),
+            (41, 1),
+            (u64::MAX, u64::MAX),
+            (0x8000_0000_0000_0000, 3),
+        ] {
+            let (circuit, public_input) = circuit(x, y);
+            assert_eq!(
+                public_input[64..],
+                to_bits(x.wrapping_add(y))
+                    .iter()
+                    .map(|b| Fp::from(*b as u64))
+                    .collect::<Vec<_>>()[..]
+            );
+            let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // Claim 41 + 1 = 43
+        let (circuit, mut public_input) = circuit(41, 1);
+        public_input[64] = Fp::one();
+        let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_gates() {
+        // out_0 = !(x & y), out_1 = x ^ 1, out_2 = x
+        let src = "5 7\n2 1 1\n1 3\n\n\
+                   2 1 0 1 2 AND\n\
+                   1 1 2 4 INV\n\
+                   1 1 1 3 EQ\n\
+                   2 1 0 3 5 XOR\n\
+                   1 1 0 6 EQW\n";
+        let circuit = Bristol::parse(src).unwrap();
+
+        for x in [false, true] {
+            for y in [false, true] {
+                let inputs = [vec![x], vec![y]];
+                let circuit =
+                    BristolCircuit::new(circuit.clone(), &inputs, vec![false, false], vec![true])
+                        .unwrap();
+                let public_input = circuit.public_signals::<Fp>(&inputs).unwrap();
+                let expected = [!(x & y), !x, x]
+                    .iter()
+                    .map(|b| Fp::from(*b as u64))
+                    .collect::<Vec<_>>();
+                assert_eq!(public_input, expected);
+
+                let prover = MockProver::run(4, &circuit, vec![public_input]).unwrap();
+                prover.assert_satisfied();
+            }
+        }
+
+        // A public input of 2 is not a bit
+        let circuit = BristolCircuit::new(
+            circuit,
+            &[vec![true], vec![true]],
+            vec![false, true],
+            vec![true],
+        )
+        .unwrap();
+        let prover = MockProver::run(
+            4,
+            &circuit,
+            vec![vec![Fp::from(2), Fp::one(), Fp::zero(), Fp::one()]],
+        )
+        .unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+
+use crate::modular::assert_limbs;
+use crate::pyth::{pythChip, pythConfig};
+use crate::rangelookup::RangeCheckConfig;
+use crate::table::{RangeTableConfig, TableLoads};
+
+// Limbs of LOOKUP_RANGE each. With LOOKUP_RANGE = 256 coordinates and the
+// radius are 16-bit and the slack is 32-bit.
+const COORD_LIMBS: usize = 2;
+const SLACK_LIMBS: usize = 4;
+
+/// Proves that a private point (x, y) lies within distance r of a center (cx, cy):
+///
+///     (x - cx)² + (y - cy)² + slack = r²
+///
+/// The squares and sums use the pythChip rows. The slack is decomposed into
+/// limbs which are range checked against the lookup table, so it is a
+/// non-negative integer. The coordinates are range checked the same way, which
+/// keeps every term far below the field modulus: without this a prover could
+/// use a field square root of a small value as a "coordinate".
+///
+/// The center and radius are passed in as cells, so the caller decides whether
+/// they are public. They are range checked like the point, since a center or
+/// radius that wraps around the field, e.g. cx = -5, would put far away points
+/// inside the circle.
+#[derive(Debug, Clone)]
+struct CircleConfig<F: FieldExt, const LOOKUP_RANGE: usize> {
+    pyth: pythConfig,
+    range_check: RangeCheckConfig<F, 2, LOOKUP_RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct CircleChip<F: FieldExt, const LOOKUP_RANGE: usize> {
+    config: CircleConfig<F, LOOKUP_RANGE>,
+}
+
+impl<F: FieldExt, const LOOKUP_RANGE: usize> CircleChip<F, LOOKUP_RANGE> {
+    pub fn construct(config: CircleConfig<F, LOOKUP_RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> CircleConfig<F, LOOKUP_RANGE> {
+        let pyth = pythChip::configure(meta);
+
+        // Range checked cells are copied in from the pyth columns
+        let value = meta.advice_column();
+        meta.enable_equality(value);
+        let range_check = RangeCheckConfig::configure(meta, value, table);
+
+        CircleConfig { pyth, range_check }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        x: Value<F>,
+        y: Value<F>,
+        cx: &AssignedCell<F, F>,
+        cy: &AssignedCell<F, F>,
+        r: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+
+        let x = pyth.load_private(layouter.namespace(|| "x"), x)?;
+        let y = pyth.load_private(layouter.namespace(|| "y"), y)?;
+        self.assert_limbs(layouter.namespace(|| "x in range"), tables, &x, COORD_LIMBS)?;
+        self.assert_limbs(layouter.namespace(|| "y in range"), tables, &y, COORD_LIMBS)?;
+        self.assert_limbs(
+            layouter.namespace(|| "cx in range"),
+            tables,
+            cx,
+            COORD_LIMBS,
+        )?;
+        self.assert_limbs(
+            layouter.namespace(|| "cy in range"),
+            tables,
+            cy,
+            COORD_LIMBS,
+        )?;
+        self.assert_limbs(layouter.namespace(|| "r in range"), tables, r, COORD_LIMBS)?;
+
+        let dx = pyth.sub(layouter.namespace(|| "x - cx"), &x, cx)?;
+        let dy = pyth.sub(layouter.namespace(|| "y - cy"), &y, cy)?;
+        let dx_sq = pyth.mul(layouter.namespace(|| "dx * dx"), &dx, &dx)?;
+        let dy_sq = pyth.mul(layouter.namespace(|| "dy * dy"), &dy, &dy)?;
+        let dist_sq = pyth.add(layouter.namespace(|| "dx² + dy²"), &dx_sq, &dy_sq)?;
+        let r_sq = pyth.mul(layouter.namespace(|| "r * r"), r, r)?;
+
+        // slack + dist² = r²
+        let slack = pyth.sub(layouter.namespace(|| "slack"), &r_sq, &dist_sq)?;
+        self.assert_limbs(
+            layouter.namespace(|| "slack >= 0"),
+            tables,
+            &slack,
+            SLACK_LIMBS,
+        )
+    }
+
+    fn assert_limbs(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        value: &AssignedCell<F, F>,
+        num_limbs: usize,
+    ) -> Result<(), Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+        assert_limbs(
+            &pyth,
+            &self.config.range_check,
+            layouter,
+            tables,
+            value,
+            num_limbs,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt> {
+        x: Value<F>,
+        y: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (CircleConfig<F, 256>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (CircleChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = CircleChip::construct(config.clone());
+
+            // Public center and radius
+            let pyth = pythChip::construct(config.pyth);
+            let cx = pyth.load_public(layouter.namespace(|| "cx"), 0)?;
+            let cy = pyth.load_public(layouter.namespace(|| "cy"), 1)?;
+            let r = pyth.load_public(layouter.namespace(|| "r"), 2)?;
+
+            chip.assign(
+                layouter.namespace(|| "circle"),
+                &tables,
+                self.x,
+                self.y,
+                &cx,
+                &cy,
+                &r,
+            )
+        }
+    }
+
+    fn run(point: (u64, u64), center: (u64, u64), r: u64) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            x: Value::known(Fp::from(point.0)),
+            y: Value::known(Fp::from(point.1)),
+        };
+        let public_input = vec![Fp::from(center.0), Fp::from(center.1), Fp::from(r)];
+
+        MockProver::run(9, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_circle() {
+        // Center, interior and boundary points
+        run((100, 100), (100, 100), 5).assert_satisfied();
+        run((103, 104), (100, 100), 5).assert_satisfied();
+        run((97, 96), (100, 100), 5).assert_satisfied();
+        run((100, 95), (100, 100), 5).assert_satisfied();
+        run((0, 0), (1000, 1000), 2000).assert_satisfied();
+        run((65535, 65535), (65535, 0), 65535).assert_satisfied();
+
+        // Just outside
+        assert!(run((104, 104), (100, 100), 5).verify().is_err());
+        assert!(run((100, 94), (100, 100), 5).verify().is_err());
+        assert!(run((0, 0), (1000, 1000), 1000).verify().is_err());
+
+        // Coordinates must fit in 16 bits
+        assert!(run((65536, 0), (65536, 0), 1).verify().is_err());
+    }
+
+    #[test]
+    fn test_circle_negative_coordinate() {
+        // x = -1 is a huge field element, even though (-1, 0) is within
+        // distance 1 of the origin
+        let circuit = MyCircuit {
+            x: Value::known(-Fp::one()),
+            y: Value::known(Fp::zero()),
+        };
+        let public_input = vec![Fp::zero(), Fp::zero(), Fp::one()];
+        let prover = MockProver::run(9, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_circle_wrapped_center() {
+        let run = |center: (Fp, Fp), r: Fp, point: (u64, u64)| {
+            let circuit = MyCircuit {
+                x: Value::known(Fp::from(point.0)),
+                y: Value::known(Fp::from(point.1)),
+            };
+            let public_input = vec![center.0, center.1, r];
+            MockProver::run(9, &circuit, vec![public_input]).unwrap()
+        };
+        let (zero, five) = (Fp::zero(), Fp::from(5));
+
+        // (0, 0) is at distance 5 from (-5, 0), but cx = p - 5 is not a
+        // coordinate
+        run((zero, zero), five, (3, 4)).assert_satisfied();
+        assert!(run((-five, zero), five, (0, 0)).verify().is_err());
+        assert!(run((zero, -five), five, (0, 0)).verify().is_err());
+
+        // r = -5 has the same square as r = 5
+        assert!(run((zero, zero), -five, (3, 4)).verify().is_err());
+    }
+}
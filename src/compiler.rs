@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::pyth::{pythChip, pythConfig};
+
+/// Compiles small arithmetic programs such as
+///
+///     pub a; pub b; pub c;
+///     a*a + b*b == c*c
+///
+/// into rows of the pythChip add and multiply gates, instead of writing out a
+/// layout by hand like pythChip::assign_all.
+///
+/// A program is a list of statements separated by `;` or newlines:
+///
+///     pub x, y      public variables, read from the instance column in order
+///     priv z        private variables, witnessed by the prover
+///     lhs == rhs    an equality between two expressions
+///
+/// Expressions are built from variables, integer constants, `+`, `-`, `*` and
+/// parentheses. Every operation becomes one row and every use of a variable is
+/// copy constrained to the cell where it was loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Var(String),
+    Const(u64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program {
+    pub public: Vec<String>,
+    pub private: Vec<String>,
+    pub constraints: Vec<(Expr, Expr)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    UnexpectedChar(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    InvalidLiteral(String),
+    UndeclaredVariable(String),
+    DuplicateVariable(String),
+    UnassignedVariable(String),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            Self::UnexpectedToken(t) => write!(f, "unexpected token {:?}", t),
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+            Self::InvalidLiteral(n) => write!(f, "literal {} does not fit in 64 bits", n),
+            Self::UndeclaredVariable(v) => write!(f, "variable {} is not declared", v),
+            Self::DuplicateVariable(v) => write!(f, "variable {} is declared twice", v),
+            Self::UnassignedVariable(v) => write!(f, "private variable {} has no witness", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Num(u64),
+    Pub,
+    Priv,
+    Plus,
+    Minus,
+    Star,
+    LParen,
+    RParen,
+    EqEq,
+    Comma,
+    Sep,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, CompileError> {
+    let mut tokens = vec![];
+    let mut chars = src.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\r' => {
+                chars.next();
+            }
+            '\n' | ';' => {
+                chars.next();
+                tokens.push(Token::Sep);
+            }
+            '+' | '-' | '*' | '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match c {
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Star,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                });
+            }
+            '=' => {
+                chars.next();
+                match chars.next() {
+                    Some('=') => tokens.push(Token::EqEq),
+                    _ => return Err(CompileError::UnexpectedChar('=')),
+                }
+            }
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                    chars.next();
+                }
+                let n = digits
+                    .parse()
+                    .map_err(|_| CompileError::InvalidLiteral(digits))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(match ident.as_str() {
+                    "pub" => Token::Pub,
+                    "priv" => Token::Priv,
+                    _ => Token::Ident(ident),
+                });
+            }
+            c => return Err(CompileError::UnexpectedChar(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, CompileError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token.ok_or(CompileError::UnexpectedEnd)
+    }
+
+    fn unexpected(token: Token) -> CompileError {
+        CompileError::UnexpectedToken(format!("{:?}", token))
+    }
+
+    // sum := term (('+' | '-') term)*
+    fn sum(&mut self) -> Result<Expr, CompileError> {
+        let mut expr = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    expr = Expr::Add(Box::new(expr), Box::new(self.term()?));
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    expr = Expr::Sub(Box::new(expr), Box::new(self.term()?));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    // term := factor ('*' factor)*
+    fn term(&mut self) -> Result<Expr, CompileError> {
+        let mut expr = self.factor()?;
+        while let Some(Token::Star) = self.peek() {
+            self.pos += 1;
+            expr = Expr::Mul(Box::new(expr), Box::new(self.factor()?));
+        }
+        Ok(expr)
+    }
+
+    // factor := ident | number | '(' sum ')'
+    fn factor(&mut self) -> Result<Expr, CompileError> {
+        match self.next()? {
+            Token::Ident(name) => Ok(Expr::Var(name)),
+            Token::Num(n) => Ok(Expr::Const(n)),
+            Token::LParen => {
+                let expr = self.sum()?;
+                match self.next()? {
+                    Token::RParen => Ok(expr),
+                    token => Err(Self::unexpected(token)),
+                }
+            }
+            token => Err(Self::unexpected(token)),
+        }
+    }
+
+    // decl := ident (',' ident)*
+    fn decl(&mut self, names: &mut Vec<String>) -> Result<(), CompileError> {
+        loop {
+            match self.next()? {
+                Token::Ident(name) => names.push(name),
+                token => return Err(Self::unexpected(token)),
+            }
+            match self.peek() {
+                Some(Token::Comma) => self.pos += 1,
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+impl Program {
+    pub fn parse(src: &str) -> Result<Self, CompileError> {
+        let mut parser = Parser {
+            tokens: tokenize(src)?,
+            pos: 0,
+        };
+        let mut program = Program::default();
+
+        while let Some(token) = parser.peek() {
+            match token {
+                Token::Sep => {
+                    parser.pos += 1;
+                    continue;
+                }
+                Token::Pub => {
+                    parser.pos += 1;
+                    parser.decl(&mut program.public)?;
+                }
+                Token::Priv => {
+                    parser.pos += 1;
+                    parser.decl(&mut program.private)?;
+                }
+                _ => {
+                    let lhs = parser.sum()?;
+                    match parser.next()? {
+                        Token::EqEq => (),
+                        token => return Err(Parser::unexpected(token)),
+                    }
+                    let rhs = parser.sum()?;
+                    program.constraints.push((lhs, rhs));
+                }
+            }
+
+            // Statements must be followed by a separator
+            match parser.peek() {
+                None | Some(Token::Sep) => (),
+                Some(token) => return Err(Parser::unexpected(token.clone())),
+            }
+        }
+
+        program.check()?;
+        Ok(program)
+    }
+
+    fn check(&self) -> Result<(), CompileError> {
+        let mut declared = vec![];
+        for name in self.public.iter().chain(self.private.iter()) {
+            if declared.contains(&name) {
+                return Err(CompileError::DuplicateVariable(name.clone()));
+            }
+            declared.push(name);
+        }
+
+        fn visit(expr: &Expr, declared: &[&String]) -> Result<(), CompileError> {
+            match expr {
+                Expr::Var(name) if !declared.contains(&name) => {
+                    Err(CompileError::UndeclaredVariable(name.clone()))
+                }
+                Expr::Var(_) | Expr::Const(_) => Ok(()),
+                Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => {
+                    visit(a, declared)?;
+                    visit(b, declared)
+                }
+            }
+        }
+        for (lhs, rhs) in self.constraints.iter() {
+            visit(lhs, &declared)?;
+            visit(rhs, &declared)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A circuit generated at runtime from a parsed Program. Public variables are
+/// read from the instance column in declaration order, private ones from
+/// `witness`, which must assign every one of them.
+#[derive(Debug, Clone, Default)]
+struct CompiledCircuit<F: FieldExt> {
+    program: Program,
+    witness: HashMap<String, Value<F>>,
+}
+
+impl<F: FieldExt> CompiledCircuit<F> {
+    pub fn new(program: Program, witness: HashMap<String, F>) -> Result<Self, CompileError> {
+        if let Some(name) = program.private.iter().find(|v| !witness.contains_key(*v)) {
+            return Err(CompileError::UnassignedVariable(name.clone()));
+        }
+
+        let witness = witness
+            .into_iter()
+            .map(|(name, value)| (name, Value::known(value)))
+            .collect();
+        Ok(Self { program, witness })
+    }
+
+    fn lower(
+        chip: &pythChip<F>,
+        mut layouter: impl Layouter<F>,
+        vars: &HashMap<String, AssignedCell<F, F>>,
+        expr: &Expr,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match expr {
+            Expr::Var(name) => Ok(vars[name].clone()),
+            Expr::Const(n) => chip.load_constant(layouter.namespace(|| "const"), F::from(*n)),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => {
+                let a = Self::lower(chip, layouter.namespace(|| "lhs"), vars, a)?;
+                let b = Self::lower(chip, layouter.namespace(|| "rhs"), vars, b)?;
+                match expr {
+                    Expr::Add(..) => chip.add(layouter.namespace(|| "add"), &a, &b),
+                    Expr::Sub(..) => chip.sub(layouter.namespace(|| "sub"), &a, &b),
+                    _ => chip.mul(layouter.namespace(|| "mul"), &a, &b),
+                }
+            }
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for CompiledCircuit<F> {
+    type Config = pythConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        let witness = self
+            .program
+            .private
+            .iter()
+            .map(|name| (name.clone(), Value::unknown()))
+            .collect();
+        Self {
+            program: self.program.clone(),
+            witness,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        pythChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = pythChip::construct(config);
+
+        let mut vars = HashMap::new();
+        for (row, name) in self.program.public.iter().enumerate() {
+            let cell = chip.load_public(layouter.namespace(|| name.as_str()), row)?;
+            vars.insert(name.clone(), cell);
+        }
+        for name in self.program.private.iter() {
+            let value = self.witness.get(name).copied().ok_or(Error::Synthesis)?;
+            let cell = chip.load_private(layouter.namespace(|| name.as_str()), value)?;
+            vars.insert(name.clone(), cell);
+        }
+
+        for (lhs, rhs) in self.program.constraints.iter() {
+            let lhs = Self::lower(&chip, layouter.namespace(|| "lhs"), &vars, lhs)?;
+            let rhs = Self::lower(&chip, layouter.namespace(|| "rhs"), &vars, rhs)?;
+            chip.assert_equal(layouter.namespace(|| "lhs == rhs"), &lhs, &rhs)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn compile(src: &str, witness: &[(&str, u64)]) -> CompiledCircuit<Fp> {
+        let program = Program::parse(src).unwrap();
+        let witness = witness
+            .iter()
+            .map(|(name, value)| (name.to_string(), Fp::from(*value)))
+            .collect();
+        CompiledCircuit::new(program, witness).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let program = Program::parse("pub a, b\npriv c; (a + 1) * b == c - 2").unwrap();
+        assert_eq!(program.public, vec!["a", "b"]);
+        assert_eq!(program.private, vec!["c"]);
+
+        let var = |name: &str| Box::new(Expr::Var(name.to_string()));
+        assert_eq!(
+            program.constraints,
+            vec![(
+                Expr::Mul(
+                    Box::new(Expr::Add(var("a"), Box::new(Expr::Const(1)))),
+                    var("b")
+                ),
+                Expr::Sub(var("c"), Box::new(Expr::Const(2))),
+            )]
+        );
+
+        // Precedence: a + b * c is a + (b * c)
+        let program = Program::parse("pub a, b, c; a + b * c == a").unwrap();
+        assert_eq!(
+            program.constraints[0].0,
+            Expr::Add(var("a"), Box::new(Expr::Mul(var("b"), var("c"))))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Program::parse("pub a; a * b == a"),
+            Err(CompileError::UndeclaredVariable("b".to_string()))
+        );
+        assert_eq!(
+            Program::parse("pub a; priv a"),
+            Err(CompileError::DuplicateVariable("a".to_string()))
+        );
+        assert_eq!(
+            Program::parse("pub a; a + == a"),
+            Err(CompileError::UnexpectedToken("EqEq".to_string()))
+        );
+        assert_eq!(
+            Program::parse("pub a; a == (a"),
+            Err(CompileError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Program::parse("pub a; a = a"),
+            Err(CompileError::UnexpectedChar('='))
+        );
+        assert_eq!(
+            Program::parse("pub a; a / a == a"),
+            Err(CompileError::UnexpectedChar('/'))
+        );
+        assert_eq!(
+            Program::parse("pub a; a == a a"),
+            Err(CompileError::UnexpectedToken("Ident(\"a\")".to_string()))
+        );
+
+        let too_big = "18446744073709551616";
+        assert_eq!(
+            Program::parse(&format!("pub a; a == {}", too_big)),
+            Err(CompileError::InvalidLiteral(too_big.to_string()))
+        );
+        assert!(Program::parse("pub a; a == 18446744073709551615").is_ok());
+    }
+
+    #[test]
+    fn test_unassigned_variable() {
+        let program = Program::parse("pub c; priv a, b; a*a + b*b == c*c").unwrap();
+        let witness = [("a".to_string(), Fp::from(5))].into_iter().collect();
+        assert_eq!(
+            CompiledCircuit::new(program, witness).unwrap_err(),
+            CompileError::UnassignedVariable("b".to_string())
+        );
+    }
+
+    // Same statement and inputs as the test in pyth.rs
+    #[test]
+    fn test_compiled_pyth() {
+        let circuit = compile("pub a; pub b; pub c\na*a + b*b == c*c", &[]);
+
+        let mut public_input = vec![Fp::from(5), Fp::from(12), Fp::from(13)];
+
+        let prover = MockProver::run(5, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[2] += Fp::one();
+        let prover = MockProver::run(5, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_compiled_private() {
+        // Private legs, public hypotenuse
+        let src = "pub c; priv a, b; a*a + b*b == c*c";
+        let public_input = vec![Fp::from(13)];
+
+        let circuit = compile(src, &[("a", 5), ("b", 12)]);
+        MockProver::run(5, &circuit, vec![public_input.clone()])
+            .unwrap()
+            .assert_satisfied();
+
+        let circuit = compile(src, &[("a", 5), ("b", 11)]);
+        let prover = MockProver::run(5, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_compiled_constants() {
+        // x³ + x + 5 == out, and a second constraint using subtraction
+        let src = "pub out; priv x\nx*x*x + x + 5 == out\nout - x*x == 26";
+
+        let circuit = compile(src, &[("x", 3)]);
+        MockProver::run(5, &circuit, vec![vec![Fp::from(35)]])
+            .unwrap()
+            .assert_satisfied();
+
+        let circuit = compile(src, &[("x", 4)]);
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(35)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+
+use crate::pyth::{pythChip, pythConfig};
+use crate::rangelookup::RangeCheckConfig;
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Proves that a public triple (a, b, c) is a primitive Pythagorean triple by
+/// exhibiting private Euclid parameters m, n with
+///
+///     a = m² - n²,   b = 2mn,   c = m² + n²
+///
+/// and m > n > 0, gcd(m, n) = 1, m + n odd.
+///
+/// The arithmetic is done with the add/multiply rows of pythChip. The side
+/// conditions are reduced to lookup range checks against 0..LOOKUP_RANGE:
+///
+///     m > n > 0      m, n - 1 and m - n - 1 are in range
+///     m + n odd      m + n = 2k + 1 with k in range
+///     gcd(m, n) = 1  x·m = y·n + 1 with x, y in range (Bézout)
+///
+/// Everything is bounded by LOOKUP_RANGE, so none of these relations can wrap
+/// around the field modulus. This limits m to less than LOOKUP_RANGE.
+#[derive(Debug, Clone)]
+struct EuclidConfig<F: FieldExt, const LOOKUP_RANGE: usize> {
+    pyth: pythConfig,
+    range_check: RangeCheckConfig<F, 2, LOOKUP_RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct EuclidChip<F: FieldExt, const LOOKUP_RANGE: usize> {
+    config: EuclidConfig<F, LOOKUP_RANGE>,
+}
+
+impl<F: FieldExt, const LOOKUP_RANGE: usize> EuclidChip<F, LOOKUP_RANGE> {
+    pub fn construct(config: EuclidConfig<F, LOOKUP_RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> EuclidConfig<F, LOOKUP_RANGE> {
+        let pyth = pythChip::configure(meta);
+
+        // Range checked cells are copied in from the pyth columns
+        let value = meta.advice_column();
+        meta.enable_equality(value);
+        let range_check = RangeCheckConfig::configure(meta, value, table);
+
+        EuclidConfig { pyth, range_check }
+    }
+
+    /// Returns the cells holding (a, b, c).
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        m: Value<F>,
+        n: Value<F>,
+    ) -> Result<[AssignedCell<F, F>; 3], Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+        let range_check = &self.config.range_check;
+
+        let one = pyth.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let m_cell = pyth.load_private(layouter.namespace(|| "m"), m)?;
+        let n_cell = pyth.load_private(layouter.namespace(|| "n"), n)?;
+
+        // a = m² - n², b = 2mn, c = m² + n²
+        let mm = pyth.mul(layouter.namespace(|| "m * m"), &m_cell, &m_cell)?;
+        let nn = pyth.mul(layouter.namespace(|| "n * n"), &n_cell, &n_cell)?;
+        let mn = pyth.mul(layouter.namespace(|| "m * n"), &m_cell, &n_cell)?;
+        let a = pyth.sub(layouter.namespace(|| "a = mm - nn"), &mm, &nn)?;
+        let b = pyth.add(layouter.namespace(|| "b = mn + mn"), &mn, &mn)?;
+        let c = pyth.add(layouter.namespace(|| "c = mm + nn"), &mm, &nn)?;
+
+        // m > n > 0
+        let n_minus_one = pyth.sub(layouter.namespace(|| "n - 1"), &n_cell, &one)?;
+        let m_minus_n = pyth.sub(layouter.namespace(|| "m - n"), &m_cell, &n_cell)?;
+        let gap = pyth.sub(layouter.namespace(|| "m - n - 1"), &m_minus_n, &one)?;
+        range_check.copy_lookup(layouter.namespace(|| "m in range"), tables, &m_cell)?;
+        range_check.copy_lookup(layouter.namespace(|| "n > 0"), tables, &n_minus_one)?;
+        range_check.copy_lookup(layouter.namespace(|| "m > n"), tables, &gap)?;
+
+        // m + n = 2k + 1
+        let k = m.zip(n).map(|(m, n)| {
+            F::from_u128((m.get_lower_128() + n.get_lower_128()).saturating_sub(1) / 2)
+        });
+        let k = pyth.load_private(layouter.namespace(|| "k"), k)?;
+        range_check.copy_lookup(layouter.namespace(|| "k in range"), tables, &k)?;
+        let m_plus_n = pyth.add(layouter.namespace(|| "m + n"), &m_cell, &n_cell)?;
+        let two_k = pyth.add(layouter.namespace(|| "k + k"), &k, &k)?;
+        let odd = pyth.add(layouter.namespace(|| "2k + 1"), &two_k, &one)?;
+        pyth.assert_equal(layouter.namespace(|| "m + n is odd"), &m_plus_n, &odd)?;
+
+        // x·m = y·n + 1
+        let (x, y) = m
+            .zip(n)
+            .map(|(m, n)| {
+                let (x, y) = bezout(m.get_lower_128(), n.get_lower_128());
+                (F::from_u128(x), F::from_u128(y))
+            })
+            .unzip();
+        let x = pyth.load_private(layouter.namespace(|| "x"), x)?;
+        let y = pyth.load_private(layouter.namespace(|| "y"), y)?;
+        range_check.copy_lookup(layouter.namespace(|| "x in range"), tables, &x)?;
+        range_check.copy_lookup(layouter.namespace(|| "y in range"), tables, &y)?;
+        let xm = pyth.mul(layouter.namespace(|| "x * m"), &x, &m_cell)?;
+        let yn = pyth.mul(layouter.namespace(|| "y * n"), &y, &n_cell)?;
+        let yn_plus_one = pyth.add(layouter.namespace(|| "y * n + 1"), &yn, &one)?;
+        pyth.assert_equal(layouter.namespace(|| "gcd(m, n) = 1"), &xm, &yn_plus_one)?;
+
+        Ok([a, b, c])
+    }
+}
+
+// Non-negative x <= n, y < m with x·m - y·n = 1. Falls back to (0, 0) when
+// gcd(m, n) != 1, which leaves the Bézout constraint unsatisfied.
+fn bezout(m: u128, n: u128) -> (u128, u128) {
+    if n == 0 {
+        return (0, 0);
+    }
+
+    // Extended Euclid: s·m + t·n = g
+    let (mut old_r, mut r) = (m as i128, n as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r != 1 {
+        return (0, 0);
+    }
+
+    let mut x = old_s.rem_euclid(n as i128) as u128;
+    if x == 0 {
+        // only when n = 1
+        x = n;
+    }
+    (x, (x * m - 1) / n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt> {
+        m: Value<F>,
+        n: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (EuclidConfig<F, 256>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (EuclidChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = EuclidChip::construct(config.clone());
+
+            let triple = chip.assign(layouter.namespace(|| "euclid"), &tables, self.m, self.n)?;
+
+            let pyth = pythChip::construct(config.pyth);
+            for (row, cell) in triple.iter().enumerate() {
+                pyth.expose_public(layouter.namespace(|| "triple"), cell, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(m: u64, n: u64, triple: [u64; 3]) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            m: Value::known(Fp::from(m)),
+            n: Value::known(Fp::from(n)),
+        };
+        let public_input = triple.iter().map(|v| Fp::from(*v)).collect();
+
+        MockProver::run(9, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_bezout() {
+        for m in 1..40u128 {
+            for n in 1..m {
+                let (x, y) = bezout(m, n);
+                let gcd = (1..=n).rev().find(|d| m % d == 0 && n % d == 0).unwrap();
+                if gcd == 1 {
+                    assert_eq!(x * m, y * n + 1);
+                    assert!(x <= n && y < m);
+                } else {
+                    assert_eq!((x, y), (0, 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_euclid() {
+        // (m, n) -> (m² - n², 2mn, m² + n²)
+        run(2, 1, [3, 4, 5]).assert_satisfied();
+        run(3, 2, [5, 12, 13]).assert_satisfied();
+        run(4, 1, [15, 8, 17]).assert_satisfied();
+        run(4, 3, [7, 24, 25]).assert_satisfied();
+        run(255, 254, [509, 129540, 129541]).assert_satisfied();
+
+        // Wrong public triple
+        assert!(run(2, 1, [3, 4, 6]).verify().is_err());
+
+        // Same parity: (8, 6, 10) is not primitive
+        assert!(run(3, 1, [8, 6, 10]).verify().is_err());
+
+        // Opposite parity but gcd(6, 3) = 3: (27, 36, 45) is not primitive
+        assert!(run(6, 3, [27, 36, 45]).verify().is_err());
+
+        // m out of range
+        assert!(run(256, 1, [65535, 512, 65537]).verify().is_err());
+    }
+
+    #[test]
+    fn test_euclid_ordering() {
+        // m < n gives a negative a
+        let circuit = MyCircuit {
+            m: Value::known(Fp::from(1)),
+            n: Value::known(Fp::from(2)),
+        };
+        let public_input = vec![-Fp::from(3), Fp::from(4), Fp::from(5)];
+        let prover = MockProver::run(9, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // n = 0 is rejected even though (1, 0, 1) satisfies a² + b² = c²
+        assert!(run(1, 0, [1, 0, 1]).verify().is_err());
+    }
+}
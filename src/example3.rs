@@ -2,7 +2,7 @@ use crate::is_zero::{IsZeroChip, IsZeroConfig};
 
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
     plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
@@ -36,6 +36,11 @@ impl<F: FieldExt> FunctionChip<F>{
 
         let is_zero_advice_column = meta.advice_column();
 
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(c);
+        meta.enable_equality(output);
+
 //q_enable - returns expression of selector
 //value - returns expression of advice
 //value_inv - advice column 
@@ -76,43 +81,68 @@ impl<F: FieldExt> FunctionChip<F>{
     pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
-        a: F,
-        b: F,
-        c: F,
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
     ) -> Result<AssignedCell<F, F>, Error> {
-        // Construct zero chip
-        let is_zero_chip = IsZeroChip::construct(self.config.a_equals_b.clone());
-        // Only one region in this chip!>!>!
-        // Is there only one rOW? 
         layouter.assign_region(
             || "f(a, b, c) = if a == b {c} else {a - b}",
             |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-                region.assign_advice(|| "a", self.config.a, 0, || Value::known(a))?;
-                region.assign_advice(|| "b", self.config.b, 0, || Value::known(b))?;
-                region.assign_advice(|| "c", self.config.c, 0, || Value::known(c))?;
-                // pass this region to chip
-                is_zero_chip.assign(&mut region, 0, Value::known(a - b))?;
-                //
-                //assigns value inv in config of is_zero_chip to be a-b
-
-                let output = if a == b { c } else { a - b };
-                region.assign_advice(|| "output", self.config.output, 0, || Value::known(output))
+                region.assign_advice(|| "a", self.config.a, 0, || a)?;
+                region.assign_advice(|| "b", self.config.b, 0, || b)?;
+                region.assign_advice(|| "c", self.config.c, 0, || c)?;
+                self.assign_output(&mut region, a, b, c)
             },
         )
     }
 
+    // Same as assign, but the inputs are copied from cells of other regions or chips
+    pub fn assign_cells(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "f(a, b, c) = if a == b {c} else {a - b}",
+            |mut region| {
+                a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                b.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+                c.copy_advice(|| "c", &mut region, self.config.c, 0)?;
+                let (a, b, c) = (a.value().copied(), b.value().copied(), c.value().copied());
+                self.assign_output(&mut region, a, b, c)
+            },
+        )
+    }
 
+    // Only one row: enables the selector, a - b for the is_zero chip and the output
+    fn assign_output(
+        &self,
+        region: &mut Region<'_, F>,
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // Construct zero chip
+        let is_zero_chip = IsZeroChip::construct(self.config.a_equals_b.clone());
 
+        self.config.selector.enable(region, 0)?;
+        // pass this region to chip
+        //assigns value inv in config of is_zero_chip to be a-b
+        is_zero_chip.assign(region, 0, a - b)?;
 
+        let output = a.zip(b).zip(c).map(|((a, b), c)| if a == b { c } else { a - b });
+        region.assign_advice(|| "output", self.config.output, 0, || output)
+    }
 }
 
 
 #[derive(Default)]
 struct FunctionCircuit<F> {
-    a: F,
-    b: F,
-    c: F,
+    a: Value<F>,
+    b: Value<F>,
+    c: Value<F>,
 }
 
 impl<F: FieldExt> Circuit<F> for FunctionCircuit<F> {
@@ -139,21 +169,105 @@ impl<F: FieldExt> Circuit<F> for FunctionCircuit<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
 
     #[test]
     fn test_example3() {
         // Make Circuit 
         let circuit = FunctionCircuit {
-            a: Fp::from(10),
-            b: Fp::from(12),
-            c: Fp::from(15),
+            a: Value::known(Fp::from(10)),
+            b: Value::known(Fp::from(12)),
+            c: Value::known(Fp::from(15)),
         };
         // 
         let prover = MockProver::run(4, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
 
+    // Feeds the outputs of earlier calls into later ones as cells. With
+    // forged_z the last call witnesses that value in place of z, still copy
+    // constrained to z like assign_cells does.
+    #[derive(Default)]
+    struct ChainedCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+        forged_z: Option<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ChainedCircuit<F> {
+        type Config = FunctionConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                forged_z: self.forged_z,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            FunctionChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = FunctionChip::construct(config);
+            // x = a - b, y = c
+            let x = chip.assign(layouter.namespace(|| "x"), self.a, self.b, self.c)?;
+            let y = chip.assign(layouter.namespace(|| "y"), self.c, self.c, self.c)?;
+            // f(x, x, y) = y, f(y, x, y) = y - x
+            let z = chip.assign_cells(layouter.namespace(|| "z"), &x, &x, &y)?;
+
+            let forged = match self.forged_z {
+                Some(forged) => forged,
+                None => {
+                    chip.assign_cells(layouter.namespace(|| "w"), &z, &x, &y)?;
+                    return Ok(());
+                }
+            };
+            layouter.assign_region(
+                || "forged w",
+                |mut region| {
+                    let a = Value::known(forged);
+                    let a_cell = region.assign_advice(|| "a", chip.config.a, 0, || a)?;
+                    region.constrain_equal(a_cell.cell(), z.cell())?;
+                    x.copy_advice(|| "b", &mut region, chip.config.b, 0)?;
+                    y.copy_advice(|| "c", &mut region, chip.config.c, 0)?;
+                    let (b, c) = (x.value().copied(), y.value().copied());
+                    chip.assign_output(&mut region, a, b, c)
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_example3_cells() {
+        let circuit = |forged_z| ChainedCircuit {
+            a: Value::known(Fp::from(10)),
+            b: Value::known(Fp::from(12)),
+            c: Value::known(Fp::from(15)),
+            forged_z,
+        };
+        let prover = MockProver::run(4, &circuit(None), vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // z = y = 15. Every gate holds for 16, only the copy of z fails
+        let prover = MockProver::run(4, &circuit(Some(Fp::from(16))), vec![]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(!failures.is_empty());
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fibo3() {
@@ -163,9 +277,9 @@ mod tests {
         let root = root.titled("Fib 3 Layout", ("sans-serif", 60)).unwrap();
 
         let circuit = FunctionCircuit {
-            a: Fp::from(10),
-            b: Fp::from(12),
-            c: Fp::from(15),
+            a: Value::known(Fp::from(10)),
+            b: Value::known(Fp::from(12)),
+            c: Value::known(Fp::from(15)),
         };
         halo2_proofs::dev::CircuitLayout::default()
             .render(4, &circuit, &root)
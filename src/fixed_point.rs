@@ -0,0 +1,290 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{ConstraintSystem, Error},
+};
+
+use crate::modular::{ModChip, ModConfig};
+use crate::pyth::pythChip;
+use crate::table::{RangeTableConfig, TableLoads};
+
+// Fractional bits, so x is stored as the integer x·2^FRAC_BITS
+pub(super) const FRAC_BITS: u32 = 32;
+
+/// Unsigned 32.32 fixed-point numbers: x is stored as the 64-bit integer
+/// x·2^32, so 0 <= x < 2^32 with a resolution of 2^-32.
+///
+///     add     a + b                   in range
+///     sub     a - b                   in range
+///     mul     a·b = q·2^32 + r        ModChip, r < 2^32, q in range
+///     div     a·2^32 = q·b + r        ModChip, r < b, b != 0, q in range
+///
+/// mul and div truncate towards zero. "In range" is 0 <= q < 2^64 with the
+/// limb lookups from ModChip, so overflow is never silent: a result that does
+/// not fit, and a negative difference, leaves the circuit unsatisfied. Inputs
+/// are assumed in range, e.g. outputs of this chip or checked with
+/// assert_in_range.
+#[derive(Debug, Clone)]
+pub(super) struct FixedPointConfig<F: FieldExt> {
+    pub(super) div: ModConfig<F, 256, 8>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct FixedPointChip<F: FieldExt> {
+    config: FixedPointConfig<F>,
+}
+
+impl<F: FieldExt> FixedPointChip<F> {
+    pub fn construct(config: FixedPointConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, 256>,
+    ) -> FixedPointConfig<F> {
+        FixedPointConfig {
+            div: ModChip::configure(meta, table, true),
+        }
+    }
+
+    /// Witnesses the encoded value x·2^32.
+    pub fn load_private(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.pyth().load_private(layouter, value)
+    }
+
+    /// Constrains value to a fixed-point number, i.e. 0 <= value < 2^64.
+    pub fn assert_in_range(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.div_chip().assert_in_range(layouter, tables, value)
+    }
+
+    /// a + b, unsatisfiable if the sum is 2^32 or more.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let sum = self.pyth().add(layouter.namespace(|| "a + b"), a, b)?;
+        self.assert_in_range(layouter.namespace(|| "no overflow"), tables, &sum)?;
+        Ok(sum)
+    }
+
+    /// a - b, unsatisfiable if b > a.
+    pub fn sub(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let diff = self.pyth().sub(layouter.namespace(|| "a - b"), a, b)?;
+        self.assert_in_range(layouter.namespace(|| "no underflow"), tables, &diff)?;
+        Ok(diff)
+    }
+
+    /// a·b truncated, unsatisfiable if the product is 2^32 or more.
+    pub fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let pyth = self.pyth();
+        let scale = pyth.load_constant(layouter.namespace(|| "2^32"), Self::scale())?;
+
+        // a·b carries 2^64, rescale to 2^32
+        let ab = pyth.mul(layouter.namespace(|| "a * b"), a, b)?;
+        let [q, _] =
+            self.div_chip()
+                .reduce(layouter.namespace(|| "a * b / 2^32"), tables, &ab, &scale)?;
+        Ok(q)
+    }
+
+    /// a / b truncated, unsatisfiable if b = 0 or the quotient is 2^32 or more.
+    pub fn div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let pyth = self.pyth();
+        let scale = pyth.load_constant(layouter.namespace(|| "2^32"), Self::scale())?;
+
+        // a/b would drop the 2^32, scale a up first
+        let scaled = pyth.mul(layouter.namespace(|| "a * 2^32"), a, &scale)?;
+        let [q, _] =
+            self.div_chip()
+                .reduce(layouter.namespace(|| "a * 2^32 / b"), tables, &scaled, b)?;
+        Ok(q)
+    }
+
+    pub fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        self.pyth().expose_public(layouter, cell, row)
+    }
+
+    fn pyth(&self) -> pythChip<F> {
+        pythChip::construct(self.config.div.pyth.clone())
+    }
+
+    fn div_chip(&self) -> ModChip<F, 256, 8> {
+        ModChip::construct(self.config.div.clone())
+    }
+
+    fn scale() -> F {
+        F::from(1 << FRAC_BITS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Clone, Copy, Debug, Default)]
+    enum Op {
+        #[default]
+        Add,
+        Sub,
+        Mul,
+        Div,
+    }
+
+    // Exposes a op b
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+        op: Op,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (FixedPointConfig<F>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (FixedPointChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = FixedPointChip::construct(config);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            let out = match self.op {
+                Op::Add => chip.add(layouter.namespace(|| "a + b"), &tables, &a, &b)?,
+                Op::Sub => chip.sub(layouter.namespace(|| "a - b"), &tables, &a, &b)?,
+                Op::Mul => chip.mul(layouter.namespace(|| "a * b"), &tables, &a, &b)?,
+                Op::Div => chip.div(layouter.namespace(|| "a / b"), &tables, &a, &b)?,
+            };
+
+            chip.expose_public(layouter.namespace(|| "out"), &out, 0)
+        }
+    }
+
+    // x·2^32 for the multiples of 2^-8 used below, so the encoding is exact
+    fn fixed(x: f64) -> u64 {
+        (x * 256.0) as u64 * (1 << 24)
+    }
+
+    fn run(a: u64, b: u64, op: Op, out: u64) -> MockProver<Fp> {
+        run_field(a, b, op, Fp::from(out))
+    }
+
+    fn run_field(a: u64, b: u64, op: Op, out: Fp) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            op,
+        };
+        MockProver::run(11, &circuit, vec![vec![out]]).unwrap()
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let (a, b) = (fixed(1.5), fixed(2.25));
+
+        run(a, b, Op::Add, fixed(3.75)).assert_satisfied();
+        run(b, a, Op::Sub, fixed(0.75)).assert_satisfied();
+        run(a, b, Op::Mul, fixed(3.375)).assert_satisfied();
+        run(fixed(3.375), a, Op::Div, b).assert_satisfied();
+        run(a, a, Op::Sub, 0).assert_satisfied();
+
+        assert!(run(a, b, Op::Add, fixed(3.5)).verify().is_err());
+        assert!(run(a, b, Op::Mul, fixed(3.5)).verify().is_err());
+    }
+
+    #[test]
+    fn test_fixed_point_truncation() {
+        // 1/3 = 0x0.5555_5555_..., and the smallest fraction squared is 0
+        let third = 0x5555_5555;
+        run(fixed(1.0), fixed(3.0), Op::Div, third).assert_satisfied();
+        run(1, 1, Op::Mul, 0).assert_satisfied();
+        run(third, fixed(3.0), Op::Mul, 0xffff_ffff).assert_satisfied();
+
+        // Rounding up is not a valid quotient
+        assert!(run(fixed(1.0), fixed(3.0), Op::Div, third + 1)
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn test_fixed_point_overflow() {
+        let max = u64::MAX;
+
+        // Largest results that fit
+        run(max - 1, 1, Op::Add, max).assert_satisfied();
+        run(max, fixed(1.0), Op::Mul, max).assert_satisfied();
+        run(max, fixed(1.0), Op::Div, max).assert_satisfied();
+
+        // The exposed outputs are the exact results over the field, which the
+        // chip computes too, so only the range checks can fail
+        let overflow = |a, b, op, out| run_field(a, b, op, out).verify().is_err();
+        assert!(overflow(max, 1, Op::Add, Fp::from_u128(1 << 64)));
+        assert!(overflow(0, 1, Op::Sub, -Fp::one()));
+        let (a, b) = (fixed(65536.0), fixed(65536.0));
+        let square = (a as u128 * b as u128) >> FRAC_BITS;
+        assert!(overflow(a, b, Op::Mul, Fp::from_u128(square)));
+        assert!(overflow(
+            max,
+            fixed(0.5),
+            Op::Div,
+            Fp::from_u128(2 * max as u128)
+        ));
+
+        // Division by zero
+        assert!(run(fixed(1.0), 0, Op::Div, 0).verify().is_err());
+    }
+}
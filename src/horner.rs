@@ -0,0 +1,256 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use std::marker::PhantomData;
+
+/// Evaluates y = P(x) = c_0 + c_1·x + ... + c_n·x^n with Horner's rule, one row
+/// per coefficient, in the same three column layout as pythConfig:
+///
+///     col_a  | col_b | col_c | coeff | q_fixed | q_advice
+///    -------------------------------------------------------
+///      0     |   x   |       |  c_n  |    1    |    0
+///     acc_1  |   x   |       | c_n-1 |    1    |    0
+///      ...   |  ...  |       |  ...  |   ...   |   ...
+///     acc_n  |   x   |       |  c_0  |    1    |    0
+///       y    |       |       |       |    0    |    0
+///
+/// acc_next = acc * x + c. Public coefficients are circuit constants in the
+/// fixed `coeff` column (q_fixed), private ones are witnessed in col_c instead
+/// (q_advice).
+#[derive(Debug, Clone)]
+struct HornerConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub coeff: Column<Fixed>,
+    pub q_fixed: Selector,
+    pub q_advice: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+struct HornerChip<F: FieldExt> {
+    config: HornerConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> HornerChip<F> {
+    pub fn construct(config: HornerConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> HornerConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let coeff = meta.fixed_column();
+        let q_fixed = meta.selector();
+        let q_advice = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("horner fixed", |meta| {
+            let s = meta.query_selector(q_fixed);
+            let acc = meta.query_advice(col_a, Rotation::cur());
+            let x = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_fixed(coeff, Rotation::cur());
+            let acc_next = meta.query_advice(col_a, Rotation::next());
+            vec![s * (acc * x + c - acc_next)]
+        });
+
+        meta.create_gate("horner advice", |meta| {
+            let s = meta.query_selector(q_advice);
+            let acc = meta.query_advice(col_a, Rotation::cur());
+            let x = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let acc_next = meta.query_advice(col_a, Rotation::next());
+            vec![s * (acc * x + c - acc_next)]
+        });
+
+        HornerConfig {
+            col_a,
+            col_b,
+            col_c,
+            coeff,
+            q_fixed,
+            q_advice,
+            instance,
+            constant,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.col_b, 0, || value),
+        )
+    }
+
+    /// P(x) with public coefficients, lowest degree first.
+    pub fn evaluate_fixed(
+        &self,
+        layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        coeffs: &[F],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let coeffs: Vec<_> = coeffs.iter().map(|c| Value::known(*c)).collect();
+        self.evaluate(layouter, x, &coeffs, true)
+    }
+
+    /// P(x) with private coefficients, lowest degree first.
+    pub fn evaluate_advice(
+        &self,
+        layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        coeffs: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.evaluate(layouter, x, coeffs, false)
+    }
+
+    fn evaluate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        coeffs: &[Value<F>],
+        fixed: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "horner",
+            |mut region| {
+                let mut acc =
+                    region.assign_advice_from_constant(|| "acc", self.config.col_a, 0, F::zero())?;
+
+                for (row, c) in coeffs.iter().rev().enumerate() {
+                    x.copy_advice(|| "x", &mut region, self.config.col_b, row)?;
+
+                    if fixed {
+                        self.config.q_fixed.enable(&mut region, row)?;
+                        region.assign_fixed(|| "coeff", self.config.coeff, row, || *c)?;
+                    } else {
+                        self.config.q_advice.enable(&mut region, row)?;
+                        region.assign_advice(|| "coeff", self.config.col_c, row, || *c)?;
+                    }
+
+                    acc = region.assign_advice(
+                        || "acc",
+                        self.config.col_a,
+                        row + 1,
+                        || acc.value().copied() * x.value() + *c,
+                    )?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        x: Value<F>,
+        coeffs: Vec<F>,
+        private: bool,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = HornerConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                x: Value::unknown(),
+                coeffs: self.coeffs.clone(),
+                private: self.private,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            HornerChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = HornerChip::construct(config);
+
+            let x = chip.load_private(layouter.namespace(|| "x"), self.x)?;
+            let y = if self.private {
+                let coeffs: Vec<_> = self.coeffs.iter().map(|c| Value::known(*c)).collect();
+                chip.evaluate_advice(layouter.namespace(|| "P(x)"), &x, &coeffs)?
+            } else {
+                chip.evaluate_fixed(layouter.namespace(|| "P(x)"), &x, &self.coeffs)?
+            };
+
+            chip.expose_public(layouter.namespace(|| "y"), &y, 0)
+        }
+    }
+
+    fn run(k: u32, x: Fp, coeffs: &[Fp], private: bool, y: Fp) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            x: Value::known(x),
+            coeffs: coeffs.to_vec(),
+            private,
+        };
+        MockProver::run(k, &circuit, vec![vec![y]]).unwrap()
+    }
+
+    #[test]
+    fn test_horner() {
+        // P(x) = 7 + 2x + 3x³
+        let coeffs = [Fp::from(7), Fp::from(2), Fp::zero(), Fp::from(3)];
+
+        for private in [false, true] {
+            run(4, Fp::from(5), &coeffs, private, Fp::from(392)).assert_satisfied();
+            run(4, Fp::zero(), &coeffs, private, Fp::from(7)).assert_satisfied();
+            assert!(run(4, Fp::from(5), &coeffs, private, Fp::from(393))
+                .verify()
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_horner_high_degree() {
+        // Degree 300, c_i = i² + 1
+        let coeffs: Vec<_> = (0..=300u64).map(|i| Fp::from(i * i + 1)).collect();
+        let x = Fp::from(3);
+        let y = coeffs.iter().rev().fold(Fp::zero(), |acc, c| acc * x + c);
+
+        for private in [false, true] {
+            run(9, x, &coeffs, private, y).assert_satisfied();
+            assert!(run(9, x + Fp::one(), &coeffs, private, y).verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// out = 1 if a == b else 0, as a cell that can be copied into other regions.
+///
+///     a | b | inv        | out | q_is_equal
+///    -----------------------------------------
+///     a | b | 1/(a-b)    |  0  |     1
+///     a | a | 0          |  1  |     1
+///
+/// IsZeroChip constrains (a - b)·(1 - (a - b)·inv) = 0, and out is constrained
+/// to equal 1 - (a - b)·inv, i.e. IsZeroConfig::expr().
+#[derive(Debug, Clone)]
+pub struct IsEqualConfig<F> {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_is_equal: Selector,
+    pub is_zero: IsZeroConfig<F>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IsEqualChip<F: FieldExt> {
+    config: IsEqualConfig<F>,
+}
+
+impl<F: FieldExt> IsEqualChip<F> {
+    pub fn construct(config: IsEqualConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> IsEqualConfig<F> {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let inv = meta.advice_column();
+        let q_is_equal = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_is_equal),
+            |meta| meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur()),
+            inv,
+        );
+
+        meta.create_gate("is equal", |meta| {
+            let s = meta.query_selector(q_is_equal);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (out - is_zero.expr())]
+        });
+
+        IsEqualConfig {
+            a,
+            b,
+            out,
+            q_is_equal,
+            is_zero,
+            instance,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    /// Returns the cell holding a == b.
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero = IsZeroChip::construct(self.config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is equal",
+            |mut region| {
+                self.config.q_is_equal.enable(&mut region, 0)?;
+
+                let a = a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+                let diff = a.value().copied() - b.value();
+                is_zero.assign(&mut region, 0, diff)?;
+
+                let out = diff.map(|diff| {
+                    if diff == F::zero() {
+                        F::one()
+                    } else {
+                        F::zero()
+                    }
+                });
+                region.assign_advice(|| "a == b", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Exposes a == b and (a == b) == c. The second comparison copies the output
+    // cell of the first one.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = IsEqualConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            IsEqualChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = IsEqualChip::construct(config);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            let c = chip.load_private(layouter.namespace(|| "c"), self.c)?;
+
+            let eq = chip.is_equal(layouter.namespace(|| "a == b"), &a, &b)?;
+            let eq_c = chip.is_equal(layouter.namespace(|| "(a == b) == c"), &eq, &c)?;
+
+            chip.expose_public(layouter.namespace(|| "a == b"), &eq, 0)?;
+            chip.expose_public(layouter.namespace(|| "(a == b) == c"), &eq_c, 1)
+        }
+    }
+
+    fn run(a: u64, b: u64, c: u64, out: [u64; 2]) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            c: Value::known(Fp::from(c)),
+        };
+        let public_input = out.iter().map(|v| Fp::from(*v)).collect();
+        MockProver::run(4, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_is_equal() {
+        run(3, 3, 1, [1, 1]).assert_satisfied();
+        run(3, 4, 1, [0, 0]).assert_satisfied();
+        run(3, 4, 0, [0, 1]).assert_satisfied();
+        run(0, 0, 5, [1, 0]).assert_satisfied();
+
+        assert!(run(3, 3, 1, [0, 0]).verify().is_err());
+        assert!(run(3, 4, 1, [1, 1]).verify().is_err());
+        assert!(run(3, 4, 0, [0, 0]).verify().is_err());
+    }
+}
@@ -0,0 +1,466 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Compares integers of N bytes (N <= 8). For a < b:
+///
+///     diff = a - b + 2^(8N)·lt
+///
+/// with diff decomposed into N bytes, each looked up in the 0..256 table. If
+/// a < b then a - b is negative and only lt = 1 brings diff into 0..2^(8N); if
+/// a >= b then lt = 1 would push it to 2^(8N) or above. a <= b uses a - b - 1
+/// in place of a - b, and a > b, a >= b swap the operands.
+///
+///     a | b | out | byte_0 | ... | byte_N-1 | q_lt | q_le
+///    -----------------------------------------------------
+///     a | b | lt  |  d_0   | ... |  d_N-1   |  1   |  0
+///     a | b | le  |  d_0   | ... |  d_N-1   |  0   |  1
+///
+/// This only holds when a and b are below 2^(8N) themselves, which the caller
+/// must ensure, e.g. with range_check.
+#[derive(Debug, Clone)]
+pub(super) struct LtConfig<F: FieldExt, const N: usize> {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub bytes: [Column<Advice>; N],
+    pub q_lt: Selector,
+    pub q_le: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+    pub(super) table: RangeTableConfig<F, 256>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct LtChip<F: FieldExt, const N: usize> {
+    config: LtConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> LtChip<F, N> {
+    pub fn construct(config: LtConfig<F, N>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, 256>,
+    ) -> LtConfig<F, N> {
+        assert!(N > 0 && N <= 8);
+
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let bytes = [(); N].map(|_| meta.advice_column());
+        let q_lt = meta.complex_selector();
+        let q_le = meta.complex_selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("less than", |meta| {
+            let q_lt = meta.query_selector(q_lt);
+            let q_le = meta.query_selector(q_le);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let diff = bytes
+                .iter()
+                .rev()
+                .fold(Expression::Constant(F::zero()), |acc, byte| {
+                    acc * F::from(256) + meta.query_advice(*byte, Rotation::cur())
+                });
+
+            let one = Expression::Constant(F::one());
+            let shifted = a - b + out.clone() * Self::range();
+            vec![
+                q_lt.clone() * (shifted.clone() - diff.clone()),
+                q_le.clone() * (shifted - one.clone() - diff),
+                (q_lt + q_le) * out.clone() * (one - out),
+            ]
+        });
+
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_lt) + meta.query_selector(q_le);
+                let byte = meta.query_advice(byte, Rotation::cur());
+                vec![(s * byte, table.value)]
+            });
+        }
+
+        LtConfig {
+            a,
+            b,
+            out,
+            bytes,
+            q_lt,
+            q_le,
+            instance,
+            constant,
+            table,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    /// a < b
+    pub fn lt(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "a < b",
+            |mut region| {
+                let lt = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| Self::int(a) < Self::int(b));
+                self.assign_row(&mut region, self.config.q_lt, a, b, lt, 0)
+            },
+        )
+    }
+
+    /// a <= b
+    pub fn le(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "a <= b",
+            |mut region| {
+                let le = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| Self::int(a) <= Self::int(b));
+                self.assign_row(&mut region, self.config.q_le, a, b, le, 1)
+            },
+        )
+    }
+
+    /// Constrains a <= b, by fixing the out cell of the a <= b row to 1.
+    pub fn assert_le(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.config.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "assert a <= b",
+            |mut region| {
+                let le = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| Self::int(a) <= Self::int(b));
+                let le = self.assign_row(&mut region, self.config.q_le, a, b, le, 1)?;
+                region.constrain_constant(le.cell(), F::one())
+            },
+        )
+    }
+
+    /// a > b
+    pub fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.lt(layouter, tables, b, a)
+    }
+
+    /// a >= b
+    pub fn ge(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.le(layouter, tables, b, a)
+    }
+
+    /// Constrains 0 <= value < 2^(8N), as a row asserting !(value < 0).
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.config.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "range check",
+            |mut region| {
+                self.config.q_lt.enable(&mut region, 0)?;
+                value.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                region.assign_advice_from_constant(|| "b", self.config.b, 0, F::zero())?;
+                region.assign_advice_from_constant(|| "lt", self.config.out, 0, F::zero())?;
+                self.assign_bytes(&mut region, value.value().map(Self::int))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    // One comparison row. diff = a - b - offset + 2^(8N)·out
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        out: Value<bool>,
+        offset: u128,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        selector.enable(region, 0)?;
+        a.copy_advice(|| "a", region, self.config.a, 0)?;
+        b.copy_advice(|| "b", region, self.config.b, 0)?;
+        let out_cell = region.assign_advice(
+            || "out",
+            self.config.out,
+            0,
+            || out.map(|out| F::from(out as u64)),
+        )?;
+
+        let diff = a.value().zip(b.value()).zip(out).map(|((a, b), out)| {
+            let range = 1u128 << (8 * N);
+            let shifted = Self::int(a) + if out { range } else { 0 };
+            // Wraps for inputs out of range, which leaves the row unsatisfied
+            shifted.wrapping_sub(Self::int(b) + offset)
+        });
+        self.assign_bytes(region, diff)?;
+
+        Ok(out_cell)
+    }
+
+    fn assign_bytes(&self, region: &mut Region<'_, F>, diff: Value<u128>) -> Result<(), Error> {
+        for (i, byte) in self.config.bytes.iter().enumerate() {
+            let value = diff.map(|diff| F::from(((diff >> (8 * i)) & 0xff) as u64));
+            region.assign_advice(|| "byte", *byte, 0, || value)?;
+        }
+        Ok(())
+    }
+
+    fn int(value: &F) -> u128 {
+        value.get_lower_128()
+    }
+
+    fn range() -> F {
+        F::from_u128(1 << (8 * N))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Exposes a < b, a <= b, a > b and a >= b for every pair, in that order
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt, const N: usize> {
+        pairs: Vec<(Value<F>, Value<F>)>,
+    }
+
+    impl<F: FieldExt, const N: usize> Circuit<F> for MyCircuit<F, N> {
+        type Config = (LtConfig<F, N>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                pairs: vec![(Value::unknown(), Value::unknown()); self.pairs.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (LtChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = LtChip::<F, N>::construct(config);
+
+            for (i, (a, b)) in self.pairs.iter().enumerate() {
+                let a = chip.load_private(layouter.namespace(|| "a"), *a)?;
+                let b = chip.load_private(layouter.namespace(|| "b"), *b)?;
+                chip.range_check(layouter.namespace(|| "a in range"), &tables, &a)?;
+                chip.range_check(layouter.namespace(|| "b in range"), &tables, &b)?;
+
+                let results = [
+                    chip.lt(layouter.namespace(|| "a < b"), &tables, &a, &b)?,
+                    chip.le(layouter.namespace(|| "a <= b"), &tables, &a, &b)?,
+                    chip.gt(layouter.namespace(|| "a > b"), &tables, &a, &b)?,
+                    chip.ge(layouter.namespace(|| "a >= b"), &tables, &a, &b)?,
+                ];
+                for (j, result) in results.iter().enumerate() {
+                    chip.expose_public(layouter.namespace(|| "result"), result, 4 * i + j)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    // a < b for every pair of bytes. Each byte is loaded once, so a pair is a
+    // single comparison row.
+    #[derive(Default)]
+    struct ExhaustiveCircuit;
+
+    impl<F: FieldExt> Circuit<F> for ExhaustiveCircuit {
+        type Config = (LtConfig<F, 1>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            MyCircuit::<F, 1>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = LtChip::<F, 1>::construct(config);
+
+            let bytes = (0..256)
+                .map(|byte| {
+                    let byte = Value::known(F::from(byte));
+                    chip.load_private(layouter.namespace(|| "byte"), byte)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (i, a) in bytes.iter().enumerate() {
+                for (j, b) in bytes.iter().enumerate() {
+                    let lt = chip.lt(layouter.namespace(|| "a < b"), &tables, a, b)?;
+                    chip.expose_public(layouter.namespace(|| "a < b"), &lt, 256 * i + j)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run<const N: usize>(k: u32, pairs: &[(u64, u64)]) -> MockProver<Fp> {
+        let circuit = MyCircuit::<Fp, N> {
+            pairs: pairs
+                .iter()
+                .map(|(a, b)| (Value::known(Fp::from(*a)), Value::known(Fp::from(*b))))
+                .collect(),
+        };
+        let public_input = pairs
+            .iter()
+            .flat_map(|(a, b)| [a < b, a <= b, a > b, a >= b])
+            .map(|result| Fp::from(result as u64))
+            .collect();
+
+        MockProver::run(k, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_less_than_exhaustive() {
+        let public_input = (0..256u64)
+            .flat_map(|a| (0..256).map(move |b| Fp::from((a < b) as u64)))
+            .collect();
+        let prover = MockProver::run(17, &ExhaustiveCircuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_less_than_bytes() {
+        // Every pair of boundary bytes in one circuit
+        let bytes = [0, 1, 2, 127, 128, 129, 254, 255];
+        let pairs: Vec<_> = bytes
+            .iter()
+            .flat_map(|a| bytes.iter().map(move |b| (*a, *b)))
+            .collect();
+        run::<1>(10, &pairs).assert_satisfied();
+    }
+
+    #[test]
+    fn test_less_than_random() {
+        // xorshift64
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut pairs = vec![
+            (0, u64::MAX),
+            (u64::MAX, u64::MAX),
+            (u64::MAX - 1, u64::MAX),
+        ];
+        for _ in 0..40 {
+            let a = next();
+            pairs.push((a, next()));
+            pairs.push((a, a));
+            pairs.push((a, a.wrapping_add(1)));
+        }
+        run::<8>(10, &pairs).assert_satisfied();
+
+        let pairs: Vec<_> = (0..40).map(|_| (next() >> 32, next() >> 32)).collect();
+        run::<4>(10, &pairs).assert_satisfied();
+    }
+
+    #[test]
+    fn test_less_than_wrong_output() {
+        // Claim 1000 >= 1001
+        let circuit = MyCircuit::<Fp, 2> {
+            pairs: vec![(Value::known(Fp::from(1000)), Value::known(Fp::from(1001)))],
+        };
+        let public_input = [1, 1, 0, 1].iter().map(|v| Fp::from(*v)).collect();
+        let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 2^16 is out of range for two bytes
+        assert!(run::<2>(10, &[(1 << 16, 3)]).verify().is_err());
+    }
+}
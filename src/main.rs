@@ -1,10 +1,33 @@
+mod all_zero;
+mod bits;
+mod bitwise;
+mod boolean;
+mod bounded_range;
+mod bristol;
+mod circle;
+mod compiler;
+mod euclid;
 mod example1Commented;
 mod example2;
-//mod example3;
-//mod is_zero;
+mod example3;
+mod fixed_point;
+mod horner;
+mod is_equal;
+mod is_zero;
+#[cfg(test)]
+mod layout;
+mod less_than;
+mod modular;
+mod plonk_gate;
 mod pyth; 
+mod r1cs;
 mod rangecheck1;
 mod rangelookup;
+mod running_sum;
+mod select;
+mod set_membership;
+mod signed;
+mod sort;
 mod table;
 
 
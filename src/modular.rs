@@ -0,0 +1,606 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+use crate::pyth::{pythChip, pythConfig};
+use crate::rangelookup::RangeCheckConfig;
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Proves a = q·m + r with 0 <= r < m:
+///
+///     q·m + r = a     pythChip multiply and add rows
+///     0 <= r          r is in range
+///     r < m           m - r - 1 is in range
+///     q               q is in range
+///     m != 0          optional, IsZeroChip on m with is_zero(m) = 0
+///
+/// "In range" means below B = LOOKUP_RANGE^LIMBS, checked with LIMBS limbs
+/// looked up in the range table. With LOOKUP_RANGE = 256, LIMBS = 4 is 32-bit
+/// and LIMBS = 8 is 64-bit integer division. Then m < 2B and q·m + r < 2B² + B,
+/// far below the field modulus, so the relation holds over the integers.
+/// Without the check on q a prover could pick r freely and let q = (a - r)/m
+/// wrap around the field.
+///
+/// m = 0 already fails r < m, as m - r - 1 wraps around. The nonzero check
+/// rejects it with a constraint of its own, for callers that divide.
+///
+/// a and m are passed in as cells, so the caller decides whether they are
+/// public.
+#[derive(Debug, Clone)]
+pub(super) struct ModConfig<F: FieldExt, const LOOKUP_RANGE: usize, const LIMBS: usize> {
+    pub(super) pyth: pythConfig,
+    range_check: RangeCheckConfig<F, 2, LOOKUP_RANGE>,
+    nonzero: Option<NonzeroConfig<F>>,
+}
+
+///     m | inv | q_nonzero
+///    ---------------------
+///     m | 1/m |    1
+#[derive(Debug, Clone)]
+struct NonzeroConfig<F: FieldExt> {
+    m: Column<Advice>,
+    q_nonzero: Selector,
+    m_is_zero: IsZeroConfig<F>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ModChip<F: FieldExt, const LOOKUP_RANGE: usize, const LIMBS: usize> {
+    config: ModConfig<F, LOOKUP_RANGE, LIMBS>,
+}
+
+impl<F: FieldExt, const LOOKUP_RANGE: usize, const LIMBS: usize> ModChip<F, LOOKUP_RANGE, LIMBS> {
+    pub fn construct(config: ModConfig<F, LOOKUP_RANGE, LIMBS>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+        check_nonzero: bool,
+    ) -> ModConfig<F, LOOKUP_RANGE, LIMBS> {
+        let pyth = pythChip::configure(meta);
+
+        // Range checked cells are copied in from the pyth columns
+        let value = meta.advice_column();
+        meta.enable_equality(value);
+        let range_check = RangeCheckConfig::configure(meta, value, table);
+
+        let nonzero = check_nonzero.then(|| {
+            let m = meta.advice_column();
+            let inv = meta.advice_column();
+            let q_nonzero = meta.selector();
+            meta.enable_equality(m);
+
+            let m_is_zero = IsZeroChip::configure(
+                meta,
+                |meta| meta.query_selector(q_nonzero),
+                |meta| meta.query_advice(m, Rotation::cur()),
+                inv,
+            );
+
+            meta.create_gate("m != 0", |meta| {
+                let s = meta.query_selector(q_nonzero);
+                vec![s * m_is_zero.expr()]
+            });
+
+            NonzeroConfig {
+                m,
+                q_nonzero,
+                m_is_zero,
+            }
+        });
+
+        ModConfig {
+            pyth,
+            range_check,
+            nonzero,
+        }
+    }
+
+    /// Returns the cells holding [q, r].
+    pub fn reduce(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        m: &AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        let (q, r) = a
+            .value()
+            .zip(m.value())
+            .map(|(a, m)| {
+                let (a, m) = (a.get_lower_128(), m.get_lower_128());
+                match m {
+                    0 => (F::zero(), F::zero()),
+                    m => (F::from_u128(a / m), F::from_u128(a % m)),
+                }
+            })
+            .unzip();
+        self.assign(layouter, tables, a, m, q, r)
+    }
+
+    /// a ≡ b (mod m)
+    pub fn assert_congruent(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        m: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+
+        let [_, r_a] = self.reduce(layouter.namespace(|| "a mod m"), tables, a, m)?;
+        let [_, r_b] = self.reduce(layouter.namespace(|| "b mod m"), tables, b, m)?;
+        pyth.assert_equal(layouter.namespace(|| "a mod m = b mod m"), &r_a, &r_b)
+    }
+
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        m: &AssignedCell<F, F>,
+        q: Value<F>,
+        r: Value<F>,
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+
+        if let Some(nonzero) = &self.config.nonzero {
+            self.assert_nonzero(layouter.namespace(|| "m != 0"), nonzero, m)?;
+        }
+
+        let one = pyth.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let q = pyth.load_private(layouter.namespace(|| "q"), q)?;
+        let r = pyth.load_private(layouter.namespace(|| "r"), r)?;
+
+        // q·m + r = a
+        let qm = pyth.mul(layouter.namespace(|| "q * m"), &q, m)?;
+        let qm_plus_r = pyth.add(layouter.namespace(|| "q * m + r"), &qm, &r)?;
+        pyth.assert_equal(layouter.namespace(|| "q * m + r = a"), &qm_plus_r, a)?;
+
+        // 0 <= r < m
+        let m_minus_r = pyth.sub(layouter.namespace(|| "m - r"), m, &r)?;
+        let gap = pyth.sub(layouter.namespace(|| "m - r - 1"), &m_minus_r, &one)?;
+        self.assert_in_range(layouter.namespace(|| "r >= 0"), tables, &r)?;
+        self.assert_in_range(layouter.namespace(|| "r < m"), tables, &gap)?;
+
+        // q cannot wrap around
+        self.assert_in_range(layouter.namespace(|| "q in range"), tables, &q)?;
+
+        Ok([q, r])
+    }
+
+    fn assert_nonzero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        config: &NonzeroConfig<F>,
+        m: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let is_zero = IsZeroChip::construct(config.m_is_zero.clone());
+
+        layouter.assign_region(
+            || "m != 0",
+            |mut region| {
+                config.q_nonzero.enable(&mut region, 0)?;
+                let m = m.copy_advice(|| "m", &mut region, config.m, 0)?;
+                is_zero.assign(&mut region, 0, m.value().copied())
+            },
+        )
+    }
+
+    /// Constrains 0 <= value < LOOKUP_RANGE^LIMBS.
+    pub fn assert_in_range(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let pyth = pythChip::construct(self.config.pyth.clone());
+        assert_limbs(
+            &pyth,
+            &self.config.range_check,
+            layouter,
+            tables,
+            value,
+            LIMBS,
+        )
+    }
+}
+
+/// Constrains value = Σ limb_i · LOOKUP_RANGE^i with every limb in the lookup
+/// table, i.e. 0 <= value < LOOKUP_RANGE^num_limbs. The recomposition uses the
+/// pyth rows and the limbs are copied into the range check. The limbs are
+/// computed from the lower 128 bits, so LOOKUP_RANGE^num_limbs is at most 2^128.
+pub(super) fn assert_limbs<F: FieldExt, const LOOKUP_RANGE: usize>(
+    pyth: &pythChip<F>,
+    range_check: &RangeCheckConfig<F, 2, LOOKUP_RANGE>,
+    mut layouter: impl Layouter<F>,
+    tables: &TableLoads,
+    value: &AssignedCell<F, F>,
+    num_limbs: usize,
+) -> Result<(), Error> {
+    let base = LOOKUP_RANGE as u128;
+    if num_limbs == 0 || base < 2 {
+        return Err(Error::Synthesis);
+    }
+    // top·base <= 2^128 without computing 2^128
+    match base.checked_pow(num_limbs as u32 - 1) {
+        Some(top) if top - 1 <= (u128::MAX - (base - 1)) / base => {}
+        _ => return Err(Error::Synthesis),
+    }
+
+    let base_cell = pyth.load_constant(layouter.namespace(|| "base"), F::from_u128(base))?;
+
+    let limbs = (0..num_limbs)
+        .map(|i| {
+            let limb = value.value().map(|v| {
+                let v = v.get_lower_128() / base.pow(i as u32);
+                F::from_u128(v % base)
+            });
+            let limb = pyth.load_private(layouter.namespace(|| "limb"), limb)?;
+            range_check.copy_lookup(layouter.namespace(|| "limb in range"), tables, &limb)?;
+            Ok(limb)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Horner from the most significant limb
+    let mut acc = limbs[num_limbs - 1].clone();
+    for limb in limbs.iter().rev().skip(1) {
+        let shifted = pyth.mul(layouter.namespace(|| "acc * base"), &acc, &base_cell)?;
+        acc = pyth.add(layouter.namespace(|| "acc + limb"), &shifted, limb)?;
+    }
+
+    pyth.assert_equal(layouter.namespace(|| "recompose"), &acc, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    // Reduces a private a modulo a public or private m and exposes r. With a
+    // witness for q and r the chip's own computation is skipped.
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt> {
+        a: Value<F>,
+        m: Value<F>,
+        public_m: bool,
+        witness: Option<(Value<F>, Value<F>)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (ModConfig<F, 256, 4>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                public_m: self.public_m,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (ModChip::configure(meta, table.clone(), false), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = ModChip::construct(config.clone());
+
+            let pyth = pythChip::construct(config.pyth);
+            let a = pyth.load_private(layouter.namespace(|| "a"), self.a)?;
+            let m = if self.public_m {
+                pyth.load_public(layouter.namespace(|| "m"), 1)?
+            } else {
+                pyth.load_private(layouter.namespace(|| "m"), self.m)?
+            };
+
+            let [_, r] = match self.witness {
+                Some((q, r)) => {
+                    chip.assign(layouter.namespace(|| "a mod m"), &tables, &a, &m, q, r)?
+                }
+                None => chip.reduce(layouter.namespace(|| "a mod m"), &tables, &a, &m)?,
+            };
+
+            pyth.expose_public(layouter.namespace(|| "r"), &r, 0)
+        }
+    }
+
+    // a ≡ b (mod m) with a, b private and m public
+    #[derive(Default)]
+    struct CongruentCircuit<F: FieldExt> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for CongruentCircuit<F> {
+        type Config = (ModConfig<F, 256, 4>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (ModChip::configure(meta, table.clone(), false), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = ModChip::construct(config.clone());
+
+            let pyth = pythChip::construct(config.pyth);
+            let a = pyth.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = pyth.load_private(layouter.namespace(|| "b"), self.b)?;
+            let m = pyth.load_public(layouter.namespace(|| "m"), 0)?;
+
+            chip.assert_congruent(layouter.namespace(|| "a = b mod m"), &tables, &a, &b, &m)
+        }
+    }
+
+    // 64-bit division with the nonzero check: divides a private a by a public
+    // d and exposes [q, r]
+    #[derive(Default)]
+    struct DivCircuit<F: FieldExt> {
+        a: Value<F>,
+        witness: Option<(Value<F>, Value<F>)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for DivCircuit<F> {
+        type Config = (ModConfig<F, 256, 8>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (ModChip::configure(meta, table.clone(), true), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = ModChip::construct(config.clone());
+
+            let pyth = pythChip::construct(config.pyth);
+            let a = pyth.load_private(layouter.namespace(|| "a"), self.a)?;
+            let d = pyth.load_public(layouter.namespace(|| "d"), 2)?;
+
+            let [q, r] = match self.witness {
+                Some((q, r)) => {
+                    chip.assign(layouter.namespace(|| "a / d"), &tables, &a, &d, q, r)?
+                }
+                None => chip.reduce(layouter.namespace(|| "a / d"), &tables, &a, &d)?,
+            };
+
+            pyth.expose_public(layouter.namespace(|| "q"), &q, 0)?;
+            pyth.expose_public(layouter.namespace(|| "r"), &r, 1)
+        }
+    }
+
+    // Range checks a private value to num_limbs limbs
+    #[derive(Default)]
+    struct LimbsCircuit<F: FieldExt> {
+        value: Value<F>,
+        num_limbs: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for LimbsCircuit<F> {
+        type Config = (ModConfig<F, 256, 4>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                num_limbs: self.num_limbs,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (ModChip::configure(meta, table.clone(), false), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+
+            let pyth = pythChip::construct(config.pyth);
+            let value = pyth.load_private(layouter.namespace(|| "value"), self.value)?;
+            assert_limbs(
+                &pyth,
+                &config.range_check,
+                layouter.namespace(|| "limbs"),
+                &tables,
+                &value,
+                self.num_limbs,
+            )
+        }
+    }
+
+    fn run(a: Fp, m: u64, public_m: bool, r: u64, witness: Option<(Fp, Fp)>) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            a: Value::known(a),
+            m: Value::known(Fp::from(m)),
+            public_m,
+            witness: witness.map(|(q, r)| (Value::known(q), Value::known(r))),
+        };
+        let public_input = vec![Fp::from(r), Fp::from(m)];
+
+        MockProver::run(10, &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn test_reduce() {
+        for public_m in [false, true] {
+            run(Fp::from(100), 7, public_m, 2, None).assert_satisfied();
+            run(Fp::from(6), 7, public_m, 6, None).assert_satisfied();
+            run(Fp::from(0), 7, public_m, 0, None).assert_satisfied();
+            run(Fp::from(4_000_000_000), 1, public_m, 0, None).assert_satisfied();
+            run(Fp::from(0xffff_ffff), 0xffff_fffe, public_m, 1, None).assert_satisfied();
+
+            assert!(run(Fp::from(100), 7, public_m, 3, None).verify().is_err());
+
+            // No r < 0
+            assert!(run(Fp::from(5), 7, public_m, 0, None).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_reduce_malicious_witness() {
+        let (a, m) = (Fp::from(100), 7);
+
+        run(a, m, true, 2, Some((Fp::from(14), Fp::from(2)))).assert_satisfied();
+
+        // r >= m is not a remainder: 100 = 13·7 + 9
+        let witness = (Fp::from(13), Fp::from(9));
+        assert!(run(a, m, true, 9, Some(witness)).verify().is_err());
+
+        // Any r with q = (a - r)/m in the field satisfies q·m + r = a
+        let r = Fp::from(5);
+        let q = (a - r) * Fp::from(m).invert().unwrap();
+        assert!(run(a, m, true, 5, Some((q, r))).verify().is_err());
+    }
+
+    #[test]
+    fn test_congruent() {
+        let run = |a: u64, b: u64, m: u64| {
+            let circuit = CongruentCircuit {
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+            };
+            MockProver::run(10, &circuit, vec![vec![Fp::from(m)]]).unwrap()
+        };
+
+        run(17, 5, 12).assert_satisfied();
+        run(1_000_003, 3, 1000).assert_satisfied();
+        assert!(run(17, 6, 12).verify().is_err());
+
+        // m = 0 has no remainders
+        assert!(run(5, 5, 0).verify().is_err());
+    }
+
+    fn run_div(a: Fp, d: u64, q: Fp, r: Fp, witness: bool) -> MockProver<Fp> {
+        let circuit = DivCircuit {
+            a: Value::known(a),
+            witness: witness.then(|| (Value::known(q), Value::known(r))),
+        };
+        MockProver::run(10, &circuit, vec![vec![q, r, Fp::from(d)]]).unwrap()
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let cases = [
+            (100, 7),
+            (6, 7),
+            (0, 1),
+            (u64::MAX, 1),
+            (u64::MAX, u64::MAX),
+            (u64::MAX, u64::MAX - 1),
+            (u64::MAX, 1 << 32),
+            (u64::MAX - 1, u64::MAX),
+        ];
+        for (a, d) in cases {
+            let (q, r) = (Fp::from(a / d), Fp::from(a % d));
+            run_div(Fp::from(a), d, q, r, false).assert_satisfied();
+        }
+
+        let wrong = run_div(Fp::from(100), 7, Fp::from(14), Fp::from(3), false);
+        assert!(wrong.verify().is_err());
+    }
+
+    #[test]
+    fn test_div_by_zero() {
+        // q·0 + 0 = 0 holds for any q, only the nonzero check catches it
+        let (a, zero) = (Fp::zero(), Fp::zero());
+        for prover in [
+            run_div(a, 0, zero, zero, false),
+            run_div(a, 0, Fp::from(5), zero, true),
+        ] {
+            let failures = prover.verify().unwrap_err();
+            assert!(failures.iter().any(|failure| matches!(
+                failure,
+                VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                    if constraint.to_string().ends_with("('m != 0')")
+            )));
+        }
+    }
+
+    #[test]
+    fn test_div_rem_malicious_witness() {
+        let (a, d) = (Fp::from(100), 7);
+
+        run_div(a, d, Fp::from(14), Fp::from(2), true).assert_satisfied();
+
+        // r >= d is not a remainder: 100 = 13·7 + 9
+        let witness = (Fp::from(13), Fp::from(9));
+        assert!(run_div(a, d, witness.0, witness.1, true).verify().is_err());
+
+        // Any r with q = (a - r)/d in the field satisfies q·d + r = a
+        let r = Fp::from(5);
+        let q = (a - r) * Fp::from(d).invert().unwrap();
+        assert!(run_div(a, d, q, r, true).verify().is_err());
+
+        // a = 2^64 needs q = 2^64 for d = 1, which is out of range
+        let a = Fp::from_u128(1 << 64);
+        assert!(run_div(a, 1, a, Fp::zero(), true).verify().is_err());
+    }
+
+    #[test]
+    fn test_assert_limbs() {
+        let run = |value: Fp, num_limbs: usize| {
+            let circuit = LimbsCircuit {
+                value: Value::known(value),
+                num_limbs,
+            };
+            MockProver::run(11, &circuit, vec![vec![]])
+        };
+
+        // 256^16 = 2^128 is the widest
+        let max = Fp::from_u128(u128::MAX);
+        run(max, 16).unwrap().assert_satisfied();
+        assert!(run(max, 15).unwrap().verify().is_err());
+        run(Fp::from(255), 1).unwrap().assert_satisfied();
+        assert!(run(Fp::from(256), 1).unwrap().verify().is_err());
+
+        assert!(matches!(run(Fp::zero(), 0), Err(Error::Synthesis)));
+        assert!(matches!(run(Fp::zero(), 17), Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,379 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance},
+    poly::Rotation,
+};
+
+use std::marker::PhantomData;
+
+/// The standard PLONK gate. Instead of one selector per operation as in
+/// pythConfig, every row carries its own coefficients in fixed columns:
+///
+///     q_l·a + q_r·b + q_m·a·b - q_o·c + q_c = 0
+///
+///      a  |  b  |  c  | q_l | q_r | q_m | q_o | q_c
+///    ---------------------------------------------------
+///      x  |  y  | x+y |  1  |  1  |  0  |  1  |  0      add
+///      x  |  y  | x*y |  0  |  0  |  1  |  1  |  0      mul
+///      x  |  0  | x+k |  1  |  0  |  0  |  1  |  k      add_constant
+///      x  |  0  | k*x |  k  |  0  |  0  |  1  |  0      mul_constant
+///      x  |  y  |  0  |  1  | -1  |  0  |  0  |  0      assert_equal
+///      x  |  0  |  0  |  1  |  0  |  0  |  0  | -k      assert_constant
+///
+/// Rows where every coefficient is zero are unconstrained, so no selector is
+/// needed.
+#[derive(Debug, Clone)]
+struct PlonkConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub c: Column<Advice>,
+    pub q_l: Column<Fixed>,
+    pub q_r: Column<Fixed>,
+    pub q_m: Column<Fixed>,
+    pub q_o: Column<Fixed>,
+    pub q_c: Column<Fixed>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct PlonkChip<F: FieldExt> {
+    config: PlonkConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> PlonkChip<F> {
+    pub fn construct(config: PlonkConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> PlonkConfig {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let c = meta.advice_column();
+        let q_l = meta.fixed_column();
+        let q_r = meta.fixed_column();
+        let q_m = meta.fixed_column();
+        let q_o = meta.fixed_column();
+        let q_c = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(c);
+        meta.enable_equality(instance);
+
+        meta.create_gate("plonk", |meta| {
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let c = meta.query_advice(c, Rotation::cur());
+            let q_l = meta.query_fixed(q_l, Rotation::cur());
+            let q_r = meta.query_fixed(q_r, Rotation::cur());
+            let q_m = meta.query_fixed(q_m, Rotation::cur());
+            let q_o = meta.query_fixed(q_o, Rotation::cur());
+            let q_c = meta.query_fixed(q_c, Rotation::cur());
+            vec![q_l * a.clone() + q_r * b.clone() + q_m * a * b - q_o * c + q_c]
+        });
+
+        PlonkConfig {
+            a,
+            b,
+            c,
+            q_l,
+            q_r,
+            q_m,
+            q_o,
+            q_c,
+            instance,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    pub fn load_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load public",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "public input",
+                    self.config.instance,
+                    row,
+                    self.config.a,
+                    0,
+                )
+            },
+        )
+    }
+
+    pub fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let c = a.value().copied() + b.value();
+        let (zero, one) = (F::zero(), F::one());
+        self.assign_gate(layouter, "add", a, Some(b), c, [one, one, zero, one, zero])
+    }
+
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let c = a.value().copied() * b.value();
+        let (zero, one) = (F::zero(), F::one());
+        self.assign_gate(layouter, "mul", a, Some(b), c, [zero, zero, one, one, zero])
+    }
+
+    pub fn add_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let c = a.value().map(|a| *a + constant);
+        let (zero, one) = (F::zero(), F::one());
+        self.assign_gate(
+            layouter,
+            "add constant",
+            a,
+            None,
+            c,
+            [one, zero, zero, one, constant],
+        )
+    }
+
+    pub fn mul_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let c = a.value().map(|a| *a * constant);
+        let (zero, one) = (F::zero(), F::one());
+        self.assign_gate(
+            layouter,
+            "mul constant",
+            a,
+            None,
+            c,
+            [constant, zero, zero, one, zero],
+        )
+    }
+
+    pub fn assert_equal(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let zero = F::zero();
+        let coeffs = [F::one(), -F::one(), zero, zero, zero];
+        self.assign_gate(
+            layouter,
+            "assert equal",
+            a,
+            Some(b),
+            Value::known(zero),
+            coeffs,
+        )?;
+        Ok(())
+    }
+
+    pub fn assert_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        constant: F,
+    ) -> Result<(), Error> {
+        let zero = F::zero();
+        let coeffs = [F::one(), zero, zero, zero, -constant];
+        self.assign_gate(
+            layouter,
+            "assert constant",
+            a,
+            None,
+            Value::known(zero),
+            coeffs,
+        )?;
+        Ok(())
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+
+    // One gate row. `b` is zero when not given, and the returned cell is `c`.
+    fn assign_gate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        a: &AssignedCell<F, F>,
+        b: Option<&AssignedCell<F, F>>,
+        c: Value<F>,
+        [q_l, q_r, q_m, q_o, q_c]: [F; 5],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                match b {
+                    Some(b) => b.copy_advice(|| "b", &mut region, self.config.b, 0)?,
+                    None => region.assign_advice(
+                        || "b",
+                        self.config.b,
+                        0,
+                        || Value::known(F::zero()),
+                    )?,
+                };
+                let c = region.assign_advice(|| "c", self.config.c, 0, || c)?;
+
+                for (column, value) in [
+                    (self.config.q_l, q_l),
+                    (self.config.q_r, q_r),
+                    (self.config.q_m, q_m),
+                    (self.config.q_o, q_o),
+                    (self.config.q_c, q_c),
+                ] {
+                    region.assign_fixed(|| "coefficient", column, 0, || Value::known(value))?;
+                }
+
+                Ok(c)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // out = 2·(x³ + x + 5) - 1, with x private and out public
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        x: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = PlonkConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            PlonkChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = PlonkChip::construct(config);
+
+            let x = chip.load_private(layouter.namespace(|| "x"), self.x)?;
+            let x2 = chip.mul(layouter.namespace(|| "x * x"), &x, &x)?;
+            let x3 = chip.mul(layouter.namespace(|| "x2 * x"), &x2, &x)?;
+            let sum = chip.add(layouter.namespace(|| "x3 + x"), &x3, &x)?;
+            let sum = chip.add_constant(layouter.namespace(|| "+ 5"), &sum, F::from(5))?;
+            let out = chip.mul_constant(layouter.namespace(|| "* 2"), &sum, F::from(2))?;
+            let out = chip.add_constant(layouter.namespace(|| "- 1"), &out, -F::one())?;
+
+            chip.expose_public(layouter.namespace(|| "out"), &out, 0)
+        }
+    }
+
+    // a² + b² = c² on public a, b, c, and c = 13
+    #[derive(Default)]
+    struct PythCircuit<F>(std::marker::PhantomData<F>);
+
+    impl<F: FieldExt> Circuit<F> for PythCircuit<F> {
+        type Config = PlonkConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            PlonkChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = PlonkChip::construct(config);
+
+            let a = chip.load_public(layouter.namespace(|| "a"), 0)?;
+            let b = chip.load_public(layouter.namespace(|| "b"), 1)?;
+            let c = chip.load_public(layouter.namespace(|| "c"), 2)?;
+
+            let aa = chip.mul(layouter.namespace(|| "a * a"), &a, &a)?;
+            let bb = chip.mul(layouter.namespace(|| "b * b"), &b, &b)?;
+            let cc = chip.mul(layouter.namespace(|| "c * c"), &c, &c)?;
+            let sum = chip.add(layouter.namespace(|| "aa + bb"), &aa, &bb)?;
+
+            chip.assert_equal(layouter.namespace(|| "aa + bb == cc"), &sum, &cc)?;
+            chip.assert_constant(layouter.namespace(|| "c == 13"), &c, F::from(13))
+        }
+    }
+
+    #[test]
+    fn test_plonk_gate() {
+        let circuit = MyCircuit {
+            x: Value::known(Fp::from(3)),
+        };
+
+        // 2 * (27 + 3 + 5) - 1
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(69)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![Fp::from(70)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_plonk_gate_asserts() {
+        let circuit = PythCircuit(std::marker::PhantomData);
+        let run = |a: u64, b: u64, c: u64| {
+            let public_input = vec![Fp::from(a), Fp::from(b), Fp::from(c)];
+            MockProver::run(5, &circuit, vec![public_input]).unwrap()
+        };
+
+        run(5, 12, 13).assert_satisfied();
+
+        // assert_equal fails
+        assert!(run(5, 11, 13).verify().is_err());
+
+        // assert_equal holds, assert_constant fails
+        assert!(run(3, 4, 5).verify().is_err());
+    }
+}
@@ -8,17 +8,18 @@ dev::MockProver,};
 use std::marker::PhantomData; 
 
 #[derive(Debug, Clone)]
-struct pythConfig{
+pub struct pythConfig{
     pub col_a: Column<Advice>,
     pub col_b: Column<Advice>,
     pub col_c: Column<Advice>,
     pub addition_selector: Selector,
     pub multi_selector: Selector,
-    pub instance: Column<Instance>
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
 }
 
 #[derive(Debug,Clone)]
-struct pythChip<F: FieldExt> {
+pub struct pythChip<F: FieldExt> {
     config: pythConfig,
     _marker: PhantomData<F>, 
 }
@@ -40,11 +41,14 @@ pub fn configure(meta: &mut ConstraintSystem<F>) -> pythConfig{
     let multi_selector = meta.selector(); 
 
     let instance = meta.instance_column();
+    // Fixed column holding the constants used by load_constant
+    let constant = meta.fixed_column();
 
     meta.enable_equality(col_a);
     meta.enable_equality(col_b);
     meta.enable_equality(col_c);
     meta.enable_equality(instance);
+    meta.enable_constant(constant);
 
     meta.create_gate("add", |meta|{
         let s = meta.query_selector(addition_selector); 
@@ -68,7 +72,8 @@ pub fn configure(meta: &mut ConstraintSystem<F>) -> pythConfig{
     col_c,
     addition_selector,
     multi_selector,
-    instance,}
+    instance,
+    constant,}
 }
 
 
@@ -156,7 +161,146 @@ pub fn assign_all(
         },
     )
 }
-/* 
+
+// The helpers below assign one gate row each, so other chips can reuse the
+// add and multiply gates without writing out a whole table like assign_all.
+
+// Witnesses a private value in col_a.
+pub fn load_private(
+    &self,
+    mut layouter: impl Layouter<F>,
+    value: Value<F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "load private",
+        |mut region| region.assign_advice(|| "private input", self.config.col_a, 0, || value),
+    )
+}
+
+// Copies the instance element at `row` into col_a.
+pub fn load_public(
+    &self,
+    mut layouter: impl Layouter<F>,
+    row: usize,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "load public",
+        |mut region| {
+            region.assign_advice_from_instance(
+                || "public input",
+                self.config.instance,
+                row,
+                self.config.col_a,
+                0,
+            )
+        },
+    )
+}
+
+// Assigns a constant to col_a, fixed by the `constant` column.
+pub fn load_constant(
+    &self,
+    mut layouter: impl Layouter<F>,
+    constant: F,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "load constant",
+        |mut region| region.assign_advice_from_constant(|| "constant", self.config.col_a, 0, constant),
+    )
+}
+
+// a | b | a + b   with the add gate on
+pub fn add(
+    &self,
+    mut layouter: impl Layouter<F>,
+    a: &AssignedCell<F, F>,
+    b: &AssignedCell<F, F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "add",
+        |mut region| {
+            self.config.addition_selector.enable(&mut region, 0)?;
+
+            a.copy_advice(|| "lhs", &mut region, self.config.col_a, 0)?;
+            b.copy_advice(|| "rhs", &mut region, self.config.col_b, 0)?;
+
+            region.assign_advice(
+                || "lhs + rhs",
+                self.config.col_c,
+                0,
+                || a.value().copied() + b.value(),
+            )
+        },
+    )
+}
+
+// a - b | b | a   with the add gate on, so no subtraction gate is needed
+pub fn sub(
+    &self,
+    mut layouter: impl Layouter<F>,
+    a: &AssignedCell<F, F>,
+    b: &AssignedCell<F, F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "sub",
+        |mut region| {
+            self.config.addition_selector.enable(&mut region, 0)?;
+
+            let diff = region.assign_advice(
+                || "lhs - rhs",
+                self.config.col_a,
+                0,
+                || a.value().copied() - b.value(),
+            )?;
+            b.copy_advice(|| "rhs", &mut region, self.config.col_b, 0)?;
+            a.copy_advice(|| "lhs", &mut region, self.config.col_c, 0)?;
+
+            Ok(diff)
+        },
+    )
+}
+
+// a | b | a * b   with the multiply gate on
+pub fn mul(
+    &self,
+    mut layouter: impl Layouter<F>,
+    a: &AssignedCell<F, F>,
+    b: &AssignedCell<F, F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "mul",
+        |mut region| {
+            self.config.multi_selector.enable(&mut region, 0)?;
+
+            a.copy_advice(|| "lhs", &mut region, self.config.col_a, 0)?;
+            b.copy_advice(|| "rhs", &mut region, self.config.col_b, 0)?;
+
+            region.assign_advice(
+                || "lhs * rhs",
+                self.config.col_c,
+                0,
+                || a.value().copied() * b.value(),
+            )
+        },
+    )
+}
+
+// Copy constraint between two cells that may live in different regions.
+pub fn assert_equal(
+    &self,
+    mut layouter: impl Layouter<F>,
+    a: &AssignedCell<F, F>,
+    b: &AssignedCell<F, F>,
+) -> Result<(), Error> {
+    layouter.assign_region(
+        || "assert equal",
+        |mut region| {
+            let lhs = a.copy_advice(|| "lhs", &mut region, self.config.col_a, 0)?;
+            region.constrain_equal(lhs.cell(), b.cell())
+        },
+    )
+}
+
 pub fn expose_public(
     &self,
     mut layouter: impl Layouter<F>,
@@ -164,7 +308,7 @@ pub fn expose_public(
     row: usize,
 ) -> Result<(), Error> {
     layouter.constrain_instance(cell.cell(), self.config.instance, row)
-}*/
+}
 
 
 }
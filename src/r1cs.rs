@@ -0,0 +1,565 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+/// Imports circom circuits from their binary `.r1cs` constraint file and `.wtns`
+/// witness file. Both formats are a magic string, a version and a list of
+/// sections:
+///
+///     "r1cs" | version = 1 | n_sections | (type: u32, size: u64, data)*
+///     "wtns" | version = 2 | n_sections | (type: u32, size: u64, data)*
+///
+/// Integers and field elements are little endian. The files must be built for
+/// the prime of F (e.g. `circom --prime pallas` for pasta::Fp).
+///
+/// Wire 0 is the constant one, followed by the public outputs, the public
+/// inputs, the private inputs and the internal signals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum R1csError {
+    BadMagic,
+    UnsupportedVersion(u32),
+    UnexpectedEof,
+    MissingSection(u32),
+    PrimeMismatch,
+    InvalidFieldElement,
+    InvalidWire(usize),
+    WitnessLength { expected: usize, actual: usize },
+}
+
+impl fmt::Display for R1csError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not an r1cs/wtns file"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            Self::UnexpectedEof => write!(f, "unexpected end of file"),
+            Self::MissingSection(t) => write!(f, "missing section of type {}", t),
+            Self::PrimeMismatch => write!(f, "file was built for a different prime"),
+            Self::InvalidFieldElement => write!(f, "field element is not canonical"),
+            Self::InvalidWire(w) => write!(f, "wire {} is out of range", w),
+            Self::WitnessLength { expected, actual } => {
+                write!(f, "expected {} witness values, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// A linear combination Σ coeff · w[wire].
+pub type LinearCombination<F> = Vec<(usize, F)>;
+
+/// <A, w> · <B, w> = <C, w>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint<F> {
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct R1cs<F> {
+    pub num_wires: usize,
+    pub num_pub_out: usize,
+    pub num_pub_in: usize,
+    pub num_prv_in: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+impl<F> R1cs<F> {
+    /// Number of public signals, i.e. wires 1..=num_public().
+    pub fn num_public(&self) -> usize {
+        self.num_pub_out + self.num_pub_in
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], R1csError> {
+        if self.bytes.len() < n {
+            return Err(R1csError::UnexpectedEof);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, R1csError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, R1csError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn usize(&mut self) -> Result<usize, R1csError> {
+        self.u32().map(|v| v as usize)
+    }
+
+    fn field<F: FieldExt>(&mut self, n8: usize) -> Result<F, R1csError> {
+        let bytes = self.take(n8)?;
+        let mut repr = F::Repr::default();
+        if repr.as_ref().len() != n8 {
+            return Err(R1csError::PrimeMismatch);
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Option::from(F::from_repr(repr)).ok_or(R1csError::InvalidFieldElement)
+    }
+
+    // Checks the file's prime against F::MODULUS and returns the element size.
+    fn prime<F: FieldExt>(&mut self) -> Result<usize, R1csError> {
+        let n8 = self.usize()?;
+        let prime: String = self
+            .take(n8)?
+            .iter()
+            .rev()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let modulus = F::MODULUS.trim_start_matches("0x").to_lowercase();
+        if prime.trim_start_matches('0') != modulus.trim_start_matches('0') {
+            return Err(R1csError::PrimeMismatch);
+        }
+        Ok(n8)
+    }
+
+    // Splits the file into its magic-checked sections.
+    fn sections(
+        bytes: &'a [u8],
+        magic: &[u8],
+        version: u32,
+    ) -> Result<Vec<(u32, Reader<'a>)>, R1csError> {
+        let mut reader = Reader { bytes };
+        if reader.take(4).map_err(|_| R1csError::BadMagic)? != magic {
+            return Err(R1csError::BadMagic);
+        }
+        let file_version = reader.u32()?;
+        if file_version != version {
+            return Err(R1csError::UnsupportedVersion(file_version));
+        }
+
+        let num_sections = reader.u32()?;
+        (0..num_sections)
+            .map(|_| {
+                let section_type = reader.u32()?;
+                let size = reader.u64()? as usize;
+                Ok((
+                    section_type,
+                    Reader {
+                        bytes: reader.take(size)?,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn section(
+        sections: &mut Vec<(u32, Reader<'a>)>,
+        section_type: u32,
+    ) -> Result<Self, R1csError> {
+        let index = sections
+            .iter()
+            .position(|(t, _)| *t == section_type)
+            .ok_or(R1csError::MissingSection(section_type))?;
+        Ok(sections.remove(index).1)
+    }
+}
+
+pub fn read_r1cs<F: FieldExt>(bytes: &[u8]) -> Result<R1cs<F>, R1csError> {
+    let mut sections = Reader::sections(bytes, b"r1cs", 1)?;
+
+    let mut header = Reader::section(&mut sections, 1)?;
+    let n8 = header.prime::<F>()?;
+    let num_wires = header.usize()?;
+    let num_pub_out = header.usize()?;
+    let num_pub_in = header.usize()?;
+    let num_prv_in = header.usize()?;
+    let _num_labels = header.u64()?;
+    let num_constraints = header.usize()?;
+
+    let mut body = Reader::section(&mut sections, 2)?;
+    let mut lc = || -> Result<LinearCombination<F>, R1csError> {
+        let num_terms = body.usize()?;
+        (0..num_terms)
+            .map(|_| {
+                let wire = body.usize()?;
+                if wire >= num_wires {
+                    return Err(R1csError::InvalidWire(wire));
+                }
+                Ok((wire, body.field(n8)?))
+            })
+            .collect()
+    };
+    let constraints = (0..num_constraints)
+        .map(|_| {
+            Ok(Constraint {
+                a: lc()?,
+                b: lc()?,
+                c: lc()?,
+            })
+        })
+        .collect::<Result<_, R1csError>>()?;
+
+    Ok(R1cs {
+        num_wires,
+        num_pub_out,
+        num_pub_in,
+        num_prv_in,
+        constraints,
+    })
+}
+
+pub fn read_wtns<F: FieldExt>(bytes: &[u8]) -> Result<Vec<F>, R1csError> {
+    let mut sections = Reader::sections(bytes, b"wtns", 2)?;
+
+    let mut header = Reader::section(&mut sections, 1)?;
+    let n8 = header.prime::<F>()?;
+    let num_witness = header.usize()?;
+
+    let mut body = Reader::section(&mut sections, 2)?;
+    (0..num_witness).map(|_| body.field(n8)).collect()
+}
+
+/// Synthesizes every R1CS constraint with pythChip style rows: a "linear
+/// combination" row per term and a "multiply" row per constraint.
+///
+///     col_a  | col_b | col_c         | coeff | q_lc | q_mul
+///    --------------------------------------------------------
+///     acc    |  w_i  | acc + k_i·w_i |  k_i  |  1   |  0
+///     <A,w>  | <B,w> | <C,w>         |       |  0   |  1
+///
+/// Each wire is assigned once (wire 0 from a constant, the public signals from
+/// the instance column) and copied into the rows that use it.
+#[derive(Debug, Clone)]
+struct R1csConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub coeff: Column<Fixed>,
+    pub q_lc: Selector,
+    pub q_mul: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+struct R1csChip<F: FieldExt> {
+    config: R1csConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> R1csChip<F> {
+    pub fn construct(config: R1csConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> R1csConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let coeff = meta.fixed_column();
+        let q_lc = meta.selector();
+        let q_mul = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("linear combination", |meta| {
+            let s = meta.query_selector(q_lc);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let k = meta.query_fixed(coeff, Rotation::cur());
+            vec![s * (a + k * b - c)]
+        });
+
+        meta.create_gate("multiply", |meta| {
+            let s = meta.query_selector(q_mul);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a * b - c)]
+        });
+
+        R1csConfig {
+            col_a,
+            col_b,
+            col_c,
+            coeff,
+            q_lc,
+            q_mul,
+            instance,
+            constant,
+        }
+    }
+
+    /// Assigns every wire. `witness` covers all wires including wire 0.
+    pub fn load_wires(
+        &self,
+        mut layouter: impl Layouter<F>,
+        r1cs: &R1cs<F>,
+        witness: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "wires",
+            |mut region| {
+                (0..r1cs.num_wires)
+                    .map(|wire| {
+                        if wire == 0 {
+                            region.assign_advice_from_constant(
+                                || "one",
+                                self.config.col_a,
+                                0,
+                                F::one(),
+                            )
+                        } else if wire <= r1cs.num_public() {
+                            region.assign_advice_from_instance(
+                                || "public signal",
+                                self.config.instance,
+                                wire - 1,
+                                self.config.col_a,
+                                wire,
+                            )
+                        } else {
+                            region.assign_advice(
+                                || "signal",
+                                self.config.col_a,
+                                wire,
+                                || witness.get(wire).copied().unwrap_or_default(),
+                            )
+                        }
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn linear_combination(
+        &self,
+        mut layouter: impl Layouter<F>,
+        wires: &[AssignedCell<F, F>],
+        lc: &LinearCombination<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "linear combination",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(
+                    || "zero",
+                    self.config.col_a,
+                    0,
+                    F::zero(),
+                )?;
+
+                for (row, (wire, k)) in lc.iter().enumerate() {
+                    self.config.q_lc.enable(&mut region, row)?;
+                    if row > 0 {
+                        acc.copy_advice(|| "acc", &mut region, self.config.col_a, row)?;
+                    }
+                    let w =
+                        wires[*wire].copy_advice(|| "w", &mut region, self.config.col_b, row)?;
+                    region.assign_fixed(|| "k", self.config.coeff, row, || Value::known(*k))?;
+                    acc = region.assign_advice(
+                        || "acc + k * w",
+                        self.config.col_c,
+                        row,
+                        || acc.value().copied() + w.value().map(|w| *w * k),
+                    )?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+
+    pub fn assign_constraint(
+        &self,
+        mut layouter: impl Layouter<F>,
+        wires: &[AssignedCell<F, F>],
+        constraint: &Constraint<F>,
+    ) -> Result<(), Error> {
+        let a = self.linear_combination(layouter.namespace(|| "A"), wires, &constraint.a)?;
+        let b = self.linear_combination(layouter.namespace(|| "B"), wires, &constraint.b)?;
+        let c = self.linear_combination(layouter.namespace(|| "C"), wires, &constraint.c)?;
+
+        layouter.assign_region(
+            || "A * B = C",
+            |mut region| {
+                self.config.q_mul.enable(&mut region, 0)?;
+                a.copy_advice(|| "A", &mut region, self.config.col_a, 0)?;
+                b.copy_advice(|| "B", &mut region, self.config.col_b, 0)?;
+                c.copy_advice(|| "C", &mut region, self.config.col_c, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+/// A circuit built from an imported R1CS. The public signals are the instance.
+#[derive(Debug, Clone, Default)]
+struct R1csCircuit<F: FieldExt> {
+    r1cs: R1cs<F>,
+    witness: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> R1csCircuit<F> {
+    pub fn new(r1cs: R1cs<F>, witness: Vec<F>) -> Result<Self, R1csError> {
+        if witness.len() != r1cs.num_wires {
+            return Err(R1csError::WitnessLength {
+                expected: r1cs.num_wires,
+                actual: witness.len(),
+            });
+        }
+        let witness = witness.into_iter().map(Value::known).collect();
+        Ok(Self { r1cs, witness })
+    }
+
+    /// The instance column: wires 1..=num_public of the witness.
+    pub fn public_signals(witness: &[F], r1cs: &R1cs<F>) -> Vec<F> {
+        witness[1..=r1cs.num_public()].to_vec()
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for R1csCircuit<F> {
+    type Config = R1csConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            r1cs: self.r1cs.clone(),
+            witness: vec![],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        R1csChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = R1csChip::construct(config);
+
+        let wires = chip.load_wires(layouter.namespace(|| "wires"), &self.r1cs, &self.witness)?;
+        for constraint in self.r1cs.constraints.iter() {
+            chip.assign_constraint(layouter.namespace(|| "constraint"), &wires, constraint)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    // See fixtures/circom/example.circom
+    const R1CS: &[u8] = include_bytes!("../fixtures/circom/example.r1cs");
+    const WTNS: &[u8] = include_bytes!("../fixtures/circom/example.wtns");
+
+    #[test]
+    fn test_read_r1cs() {
+        let r1cs = read_r1cs::<Fp>(R1CS).unwrap();
+        assert_eq!(r1cs.num_wires, 6);
+        assert_eq!(r1cs.num_pub_out, 1);
+        assert_eq!(r1cs.num_pub_in, 1);
+        assert_eq!(r1cs.num_prv_in, 2);
+
+        // m = a * b
+        // d = (m + 3c - 1) * (a - b)
+        let one = Fp::one();
+        assert_eq!(
+            r1cs.constraints,
+            vec![
+                Constraint {
+                    a: vec![(2, one)],
+                    b: vec![(3, one)],
+                    c: vec![(5, one)],
+                },
+                Constraint {
+                    a: vec![(0, -one), (4, Fp::from(3)), (5, one)],
+                    b: vec![(2, one), (3, -one)],
+                    c: vec![(1, one)],
+                },
+            ]
+        );
+
+        let witness = read_wtns::<Fp>(WTNS).unwrap();
+        let expected: Vec<_> = [1, 104, 7, 3, 2, 21].iter().map(|v| Fp::from(*v)).collect();
+        assert_eq!(witness, expected);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert_eq!(read_r1cs::<Fp>(WTNS), Err(R1csError::BadMagic));
+        assert_eq!(read_wtns::<Fp>(&WTNS[..100]), Err(R1csError::UnexpectedEof));
+
+        // Flip a byte of the prime in the header section
+        let mut bytes = R1CS.to_vec();
+        bytes[30] ^= 1;
+        assert_eq!(read_r1cs::<Fp>(&bytes), Err(R1csError::PrimeMismatch));
+
+        let mut bytes = WTNS.to_vec();
+        bytes[4] = 3;
+        assert_eq!(
+            read_wtns::<Fp>(&bytes),
+            Err(R1csError::UnsupportedVersion(3))
+        );
+
+        // A witness value equal to the modulus
+        let mut bytes = WTNS.to_vec();
+        let prime = bytes[28..60].to_vec();
+        let len = bytes.len();
+        bytes[len - 32..].copy_from_slice(&prime);
+        assert_eq!(read_wtns::<Fp>(&bytes), Err(R1csError::InvalidFieldElement));
+    }
+
+    #[test]
+    fn test_r1cs_circuit() {
+        let r1cs = read_r1cs::<Fp>(R1CS).unwrap();
+        let witness = read_wtns::<Fp>(WTNS).unwrap();
+
+        // d = 104, a = 7
+        let public_input = R1csCircuit::public_signals(&witness, &r1cs);
+        assert_eq!(public_input, vec![Fp::from(104), Fp::from(7)]);
+
+        let circuit = R1csCircuit::new(r1cs.clone(), witness.clone()).unwrap();
+        let prover = MockProver::run(5, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // Wrong public output
+        let prover = MockProver::run(5, &circuit, vec![vec![Fp::from(105), Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Tampered private signal m
+        let mut bad_witness = witness.clone();
+        bad_witness[5] += Fp::one();
+        let circuit = R1csCircuit::new(r1cs.clone(), bad_witness).unwrap();
+        let prover = MockProver::run(5, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        assert_eq!(
+            R1csCircuit::new(r1cs, witness[..5].to_vec()).unwrap_err(),
+            R1csError::WitnessLength {
+                expected: 6,
+                actual: 5
+            }
+        );
+    }
+}
@@ -21,23 +21,29 @@ use table::*;
 
 #[derive(Debug, Clone)]
 /// A range-constrained value in the circuit produced by the RangeCheckConfig.
-struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+pub(super) struct RangeConstrained<F: FieldExt, const RANGE: usize>(pub(super) AssignedCell<Assigned<F>, F>);
 
 #[derive(Debug, Clone)]
-struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
+pub(super) struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
     q_range_check: Selector,
     q_lookup: Selector,
     value: Column<Advice>,
-    table: RangeTableConfig<F, LOOKUP_RANGE>,
+    pub(super) table: RangeTableConfig<F, LOOKUP_RANGE>,
 }
 
 impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
     RangeCheckConfig<F, RANGE, LOOKUP_RANGE>
 {
-    pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+    /// The table may be shared with other configs and advice columns, and is
+    /// loaded by whoever configured it. The lookup methods take the TableLoads
+    /// it was loaded with.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> Self {
         let q_range_check = meta.selector();
         let q_lookup = meta.complex_selector();
-        let table = RangeTableConfig::configure(meta);
 
         meta.create_gate("range check", |meta| {
             //        value     |    q_range_check
@@ -98,8 +104,10 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
     pub fn assign_lookup(
         &self,
         mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
         value: Value<Assigned<F>>,
     ) -> Result<RangeConstrained<F, LOOKUP_RANGE>, Error> {
+        self.table.check_loaded(tables)?;
         layouter.assign_region(
             || "Assign value for lookup range check",
             |mut region| {
@@ -115,6 +123,89 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
             },
         )
     }
+
+    /// Simple range checks on many values in consecutive rows of one region,
+    /// instead of one region per value.
+    pub fn assign_simple_batch(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[Value<Assigned<F>>],
+    ) -> Result<Vec<RangeConstrained<F, RANGE>>, Error> {
+        let cells = self.assign_batch(
+            layouter,
+            "Assign values for simple range check",
+            self.q_range_check,
+            values,
+        )?;
+        Ok(cells.into_iter().map(RangeConstrained).collect())
+    }
+
+    /// Lookup range checks on many values in consecutive rows of one region.
+    pub fn assign_lookup_batch(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        values: &[Value<Assigned<F>>],
+    ) -> Result<Vec<RangeConstrained<F, LOOKUP_RANGE>>, Error> {
+        self.table.check_loaded(tables)?;
+        let cells = self.assign_batch(
+            layouter,
+            "Assign values for lookup range check",
+            self.q_lookup,
+            values,
+        )?;
+        Ok(cells.into_iter().map(RangeConstrained).collect())
+    }
+
+    /// Lookup range check on a cell assigned by another chip. The cell is copied
+    /// into `value`, so equality must be enabled on that column.
+    pub fn copy_lookup(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<RangeConstrained<F, LOOKUP_RANGE>, Error> {
+        self.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "Copy value for lookup range check",
+            |mut region| {
+                let offset = 0;
+
+                // Enable q_lookup
+                self.q_lookup.enable(&mut region, offset)?;
+
+                // Copy value
+                let copied =
+                    region.assign_advice(|| "value", self.value, offset, || cell.value_field())?;
+                region.constrain_equal(cell.cell(), copied.cell())?;
+
+                Ok(RangeConstrained(copied))
+            },
+        )
+    }
+
+    // Assigns value i at offset i with the selector enabled on every row
+    fn assign_batch(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        selector: Selector,
+        values: &[Value<Assigned<F>>],
+    ) -> Result<Vec<AssignedCell<Assigned<F>, F>>, Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, value)| {
+                        selector.enable(&mut region, offset)?;
+                        region.assign_advice(|| "value", self.value, offset, || *value)
+                    })
+                    .collect()
+            },
+        )
+    }
 }
 
 #[cfg(test)]
@@ -127,6 +218,8 @@ mod tests {
     };
 
     use super::*;
+    use crate::layout::Layout;
+    use crate::running_sum::RunningSumConfig;
 
     #[derive(Default)]
     struct MyCircuit<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
@@ -146,7 +239,8 @@ mod tests {
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
             let value = meta.advice_column();
-            RangeCheckConfig::configure(meta, value)
+            let table = RangeTableConfig::configure(meta);
+            RangeCheckConfig::configure(meta, value, table)
         }
 
         fn synthesize(
@@ -154,11 +248,13 @@ mod tests {
             config: Self::Config,
             mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
-            config.table.load(&mut layouter)?;
+            let tables = TableLoads::default();
+            config.table.load(&mut layouter, &tables)?;
 
             config.assign_simple(layouter.namespace(|| "Assign simple value"), self.value)?;
             config.assign_lookup(
                 layouter.namespace(|| "Assign lookup value"),
+                &tables,
                 self.lookup_value,
             )?;
 
@@ -215,6 +311,238 @@ mod tests {
         }
     }
 
+    // Range checks every value with one region per value, or all of them in
+    // one region each for the simple and lookup checks
+    #[derive(Default)]
+    struct BatchCircuit<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
+        values: Vec<Value<Assigned<F>>>,
+        lookup_values: Vec<Value<Assigned<F>>>,
+        batch: bool,
+    }
+
+    impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> Circuit<F>
+        for BatchCircuit<F, RANGE, LOOKUP_RANGE>
+    {
+        type Config = RangeCheckConfig<F, RANGE, LOOKUP_RANGE>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                lookup_values: vec![Value::unknown(); self.lookup_values.len()],
+                batch: self.batch,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let table = RangeTableConfig::configure(meta);
+            RangeCheckConfig::configure(meta, value, table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let tables = TableLoads::default();
+            config.table.load(&mut layouter, &tables)?;
+
+            if self.batch {
+                config.assign_simple_batch(layouter.namespace(|| "simple"), &self.values)?;
+                config.assign_lookup_batch(
+                    layouter.namespace(|| "lookup"),
+                    &tables,
+                    &self.lookup_values,
+                )?;
+                return Ok(());
+            }
+
+            for value in self.values.iter() {
+                config.assign_simple(layouter.namespace(|| "simple"), *value)?;
+            }
+            for value in self.lookup_values.iter() {
+                config.assign_lookup(layouter.namespace(|| "lookup"), &tables, *value)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn batch_circuit(
+        values: &[u64],
+        lookup_values: &[u64],
+        batch: bool,
+    ) -> BatchCircuit<Fp, 8, 256> {
+        let assigned = |values: &[u64]| {
+            values
+                .iter()
+                .map(|v| Value::known(Fp::from(*v).into()))
+                .collect()
+        };
+        BatchCircuit {
+            values: assigned(values),
+            lookup_values: assigned(lookup_values),
+            batch,
+        }
+    }
+
+    #[test]
+    fn test_range_check_batch() {
+        let values: Vec<u64> = (0..8).collect();
+        let lookup_values: Vec<u64> = (0..256).step_by(5).collect();
+        let circuit = batch_circuit(&values, &lookup_values, true);
+        MockProver::run(10, &circuit, vec![])
+            .unwrap()
+            .assert_satisfied();
+
+        // Failures point at the offset of the value in the batch region
+        let circuit = batch_circuit(&[1, 2, 8], &[0, 256, 3], true);
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert_eq!(
+            prover.verify(),
+            Err(vec![
+                VerifyFailure::ConstraintNotSatisfied {
+                    constraint: ((0, "range check").into(), 0, "range check").into(),
+                    location: FailureLocation::InRegion {
+                        region: (1, "Assign values for simple range check").into(),
+                        offset: 2
+                    },
+                    cell_values: vec![(((Any::Advice, 0).into(), 0).into(), "0x8".to_string())]
+                },
+                VerifyFailure::Lookup {
+                    lookup_index: 0,
+                    location: FailureLocation::InRegion {
+                        region: (2, "Assign values for lookup range check").into(),
+                        offset: 1
+                    }
+                }
+            ])
+        );
+    }
+
+    // A layout comparison, not a timing benchmark: it only counts regions and
+    // rows. V1 packs the single-row regions as tightly as the batch, so the
+    // rows are the same either way. The batch saves the regions, one per value.
+    #[test]
+    fn test_range_check_batch_layout() {
+        let values: Vec<u64> = (0..1000).map(|i| i % 8).collect();
+        let lookup_values: Vec<u64> = (0..1000).map(|i| i % 256).collect();
+        let measure = |batch| Layout::measure(&batch_circuit(&values, &lookup_values, batch));
+
+        // A region per value and one for the table
+        let per_value = measure(false).unwrap();
+        assert_eq!(per_value.regions, values.len() + lookup_values.len() + 1);
+        assert_eq!(per_value.rows, values.len() + lookup_values.len());
+
+        // One region per kind of check
+        let batch = measure(true).unwrap();
+        assert_eq!(batch.regions, 3);
+        assert_eq!(batch.rows, per_value.rows);
+    }
+
+    // Two advice columns and a running sum range check, all looking up into
+    // one table. The table is loaded `loads` times.
+    #[derive(Default)]
+    struct SharedTableCircuit<F: FieldExt> {
+        a: Value<Assigned<F>>,
+        b: Value<Assigned<F>>,
+        wide: Value<F>,
+        loads: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for SharedTableCircuit<F> {
+        type Config = (
+            RangeTableConfig<F, 256>,
+            [RangeCheckConfig<F, 8, 256>; 2],
+            RunningSumConfig<F, 256>,
+        );
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                loads: self.loads,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            let range_checks = [(); 2].map(|_| {
+                let value = meta.advice_column();
+                RangeCheckConfig::configure(meta, value, table.clone())
+            });
+            let running_sum = RunningSumConfig::configure(meta, table.clone());
+            (table, range_checks, running_sum)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (table, [check_a, check_b], running_sum) = config;
+            let tables = TableLoads::default();
+            for _ in 0..self.loads {
+                table.load(&mut layouter, &tables)?;
+            }
+
+            check_a.assign_lookup(layouter.namespace(|| "a"), &tables, self.a)?;
+            check_b.assign_lookup(layouter.namespace(|| "b"), &tables, self.b)?;
+            running_sum.witness_check(layouter.namespace(|| "wide"), &tables, self.wide, 24)?;
+
+            Ok(())
+        }
+    }
+
+    fn shared_table_circuit(a: u64, b: u64, wide: u64, loads: usize) -> SharedTableCircuit<Fp> {
+        SharedTableCircuit {
+            a: Value::known(Fp::from(a).into()),
+            b: Value::known(Fp::from(b).into()),
+            wide: Value::known(Fp::from(wide)),
+            loads,
+        }
+    }
+
+    #[test]
+    fn test_range_check_shared_table() {
+        let circuit = shared_table_circuit(0, 255, 0xab_cdef, 1);
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // The second column's lookup uses the same table
+        let circuit = shared_table_circuit(0, 256, 0xab_cdef, 1);
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert_eq!(
+            prover.verify(),
+            Err(vec![VerifyFailure::Lookup {
+                lookup_index: 1,
+                location: FailureLocation::InRegion {
+                    region: (2, "Assign value for lookup range check").into(),
+                    offset: 0
+                }
+            }])
+        );
+    }
+
+    #[test]
+    fn test_range_check_shared_table_loaded_twice() {
+        // TableLoads rejects the second load, before the floor planner sees
+        // the table column assigned twice
+        let circuit = shared_table_circuit(0, 0, 0, 2);
+        let result = MockProver::run(10, &circuit, vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+
+    #[test]
+    fn test_range_check_shared_table_not_loaded() {
+        // An unloaded table is all zeros, so these lookups of 0 would pass
+        // without the load check
+        let circuit = shared_table_circuit(0, 0, 0, 0);
+        let result = MockProver::run(10, &circuit, vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_range_check_2() {
@@ -0,0 +1,292 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Range checks a value of any num_bits (1 to 128) against the
+/// LOOKUP_RANGE = 2^K table, by splitting it into K-bit windows with a
+/// running sum from the least significant end:
+///
+///     z_0 = value,    z_i+1 = (z_i - k_i) / 2^K
+///
+/// Each window k_i = z_i - 2^K·z_i+1 is looked up. For num_bits = W·K the last
+/// z_W must be 0. Otherwise the remaining r = num_bits - W·K bits are z_W
+/// itself, and z_W < 2^r is checked by looking up both z_W and z_W·2^(K-r):
+///
+///        z             | shift   | q_lookup | q_running | q_shift
+///       ----------------------------------------------------------
+///        z_0           |         |    1     |     1     |    0
+///        ...           |         |    1     |     1     |    0
+///        z_W           |         |    1     |     0     |    0
+///        z_W·2^(K-r)   | 2^(K-r) |    1     |     0     |    1
+///
+/// The last two rows are only there for a partial window. With q_running off
+/// the lookup checks z itself.
+#[derive(Debug, Clone)]
+pub(super) struct RunningSumConfig<F: FieldExt, const LOOKUP_RANGE: usize> {
+    q_lookup: Selector,
+    q_running: Selector,
+    q_shift: Selector,
+    z: Column<Advice>,
+    shift: Column<Fixed>,
+    pub(super) table: RangeTableConfig<F, LOOKUP_RANGE>,
+}
+
+impl<F: FieldExt, const LOOKUP_RANGE: usize> RunningSumConfig<F, LOOKUP_RANGE> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> Self {
+        assert!(LOOKUP_RANGE.is_power_of_two() && LOOKUP_RANGE > 1);
+
+        let q_lookup = meta.complex_selector();
+        let q_running = meta.complex_selector();
+        let q_shift = meta.selector();
+        let z = meta.advice_column();
+        let shift = meta.fixed_column();
+
+        meta.enable_equality(z);
+        meta.enable_constant(shift);
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let q_running = meta.query_selector(q_running);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+
+            // k_i = z_i - 2^K·z_i+1 on running rows, z itself otherwise
+            let window = z_cur.clone() - z_next * F::from(LOOKUP_RANGE as u64);
+            let one = Expression::Constant(F::one());
+            let value = q_running.clone() * window + (one - q_running) * z_cur;
+
+            vec![(q_lookup * value, table.value)]
+        });
+
+        meta.create_gate("shifted window", |meta| {
+            let s = meta.query_selector(q_shift);
+            let z_prev = meta.query_advice(z, Rotation::prev());
+            let z = meta.query_advice(z, Rotation::cur());
+            let shift = meta.query_fixed(shift, Rotation::cur());
+            vec![s * (z_prev * shift - z)]
+        });
+
+        Self {
+            q_lookup,
+            q_running,
+            q_shift,
+            z,
+            shift,
+            table,
+        }
+    }
+
+    /// Witnesses value and range checks it to 0..2^num_bits.
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        value: Value<F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "witness running sum range check",
+            |mut region| {
+                let z_0 = region.assign_advice(|| "z_0", self.z, 0, || value)?;
+                self.assign(&mut region, &z_0, num_bits)?;
+                Ok(z_0)
+            },
+        )
+    }
+
+    /// Range checks a cell assigned by another chip to 0..2^num_bits. The cell
+    /// is copied into `z`.
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        cell: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        self.table.check_loaded(tables)?;
+        layouter.assign_region(
+            || "copy running sum range check",
+            |mut region| {
+                let z_0 = cell.copy_advice(|| "z_0", &mut region, self.z, 0)?;
+                self.assign(&mut region, &z_0, num_bits)
+            },
+        )
+    }
+
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        z_0: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        if num_bits == 0 || num_bits > 128 {
+            return Err(Error::Synthesis);
+        }
+        let k = Self::window_bits();
+        let (windows, remainder) = (num_bits / k, num_bits % k);
+
+        // z_i = value >> (K·i). Values of 128 bits or more come out wrong and
+        // fail the constraints
+        let value = z_0.value().map(|v| v.get_lower_128());
+        let z = |i: usize| value.map(|v| F::from_u128(v.checked_shr((k * i) as u32).unwrap_or(0)));
+
+        for row in 0..windows {
+            self.q_lookup.enable(region, row)?;
+            self.q_running.enable(region, row)?;
+            if row > 0 {
+                region.assign_advice(|| "z", self.z, row, || z(row))?;
+            }
+        }
+
+        let z_last = if windows == 0 {
+            z_0.clone()
+        } else {
+            region.assign_advice(|| "z", self.z, windows, || z(windows))?
+        };
+
+        if remainder == 0 {
+            return region.constrain_constant(z_last.cell(), F::zero());
+        }
+
+        // z_W < 2^K and z_W·2^(K-r) < 2^K
+        let shift = F::from(1 << (k - remainder));
+        self.q_lookup.enable(region, windows)?;
+        self.q_lookup.enable(region, windows + 1)?;
+        self.q_shift.enable(region, windows + 1)?;
+        region.assign_fixed(|| "shift", self.shift, windows + 1, || Value::known(shift))?;
+        let shifted = z_last.value().map(|z| *z * shift);
+        region.assign_advice(|| "shifted z", self.z, windows + 1, || shifted)?;
+
+        Ok(())
+    }
+
+    fn window_bits() -> usize {
+        LOOKUP_RANGE.trailing_zeros() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Range checks every value to num_bits
+    #[derive(Default)]
+    struct MyCircuit<F, const LOOKUP_RANGE: usize> {
+        values: Vec<Value<F>>,
+        num_bits: usize,
+    }
+
+    impl<F: FieldExt, const LOOKUP_RANGE: usize> Circuit<F> for MyCircuit<F, LOOKUP_RANGE> {
+        type Config = RunningSumConfig<F, LOOKUP_RANGE>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            RunningSumConfig::configure(meta, table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let tables = TableLoads::default();
+            config.table.load(&mut layouter, &tables)?;
+
+            for value in self.values.iter() {
+                let cell = config.witness_check(
+                    layouter.namespace(|| "value"),
+                    &tables,
+                    *value,
+                    self.num_bits,
+                )?;
+                config.copy_check(layouter.namespace(|| "copy"), &tables, &cell, self.num_bits)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run<const LOOKUP_RANGE: usize>(values: &[u128], num_bits: usize) -> MockProver<Fp> {
+        let circuit = MyCircuit::<Fp, LOOKUP_RANGE> {
+            values: values
+                .iter()
+                .map(|v| Value::known(Fp::from_u128(*v)))
+                .collect(),
+            num_bits,
+        };
+        MockProver::run(9, &circuit, vec![]).unwrap()
+    }
+
+    fn max(num_bits: usize) -> u128 {
+        u128::MAX >> (128 - num_bits)
+    }
+
+    #[test]
+    fn test_running_sum() {
+        for num_bits in [1, 7, 8, 12, 16, 63, 64, 100, 128] {
+            let values = [0, 1, max(num_bits) / 3, max(num_bits) - 1, max(num_bits)];
+            run::<256>(&values, num_bits).assert_satisfied();
+        }
+
+        run::<16>(&[0, 0x3ff, 0x155], 10).assert_satisfied();
+    }
+
+    #[test]
+    fn test_running_sum_out_of_range() {
+        for num_bits in [1, 7, 8, 12, 16, 63, 64, 100] {
+            let too_big = max(num_bits) + 1;
+            assert!(run::<256>(&[too_big], num_bits).verify().is_err());
+            assert!(run::<256>(&[u128::MAX], num_bits).verify().is_err());
+        }
+
+        assert!(run::<16>(&[0x400], 10).verify().is_err());
+
+        // 2^128 and -1 for 128 bits
+        let circuit = MyCircuit::<Fp, 256> {
+            values: vec![Value::known(Fp::from_u128(u128::MAX) + Fp::one())],
+            num_bits: 128,
+        };
+        assert!(MockProver::run(9, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_err());
+        let circuit = MyCircuit::<Fp, 256> {
+            values: vec![Value::known(-Fp::one())],
+            num_bits: 128,
+        };
+        assert!(MockProver::run(9, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn test_running_sum_bad_width() {
+        for num_bits in [0, 129] {
+            let circuit = MyCircuit::<Fp, 256> {
+                values: vec![Value::known(Fp::zero())],
+                num_bits,
+            };
+            let result = MockProver::run(9, &circuit, vec![]);
+            assert!(matches!(result, Err(Error::Synthesis)));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// Conditional select, the branching in FunctionChip (example3.rs) as a gadget
+/// over assigned cells:
+///
+///     a    | b | x | y | inv     | out            | selector
+///    ------------------------------------------------------------
+///     cond |   | x | y |         | cond ? x : y   | q_select
+///     a    | b | x | y | 1/(a-b) | a == b ? x : y | q_select_eq
+///
+/// select constrains cond to be a bit and out = cond·x + (1 - cond)·y.
+/// select_by_equality uses IsZeroChip on a - b as the condition instead.
+#[derive(Debug, Clone)]
+pub struct SelectConfig<F> {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub x: Column<Advice>,
+    pub y: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_select: Selector,
+    pub q_select_eq: Selector,
+    pub a_equals_b: IsZeroConfig<F>,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectChip<F: FieldExt> {
+    config: SelectConfig<F>,
+}
+
+impl<F: FieldExt> SelectChip<F> {
+    pub fn construct(config: SelectConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> SelectConfig<F> {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let out = meta.advice_column();
+        let inv = meta.advice_column();
+        let q_select = meta.selector();
+        let q_select_eq = meta.selector();
+        let instance = meta.instance_column();
+
+        for column in [a, b, x, y, out] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // cond·x + (1 - cond)·y
+        let select = |cond: Expression<F>, x: Expression<F>, y: Expression<F>| {
+            cond.clone() * x + (Expression::Constant(F::one()) - cond) * y
+        };
+
+        meta.create_gate("select", |meta| {
+            let s = meta.query_selector(q_select);
+            let cond = meta.query_advice(a, Rotation::cur());
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                s.clone() * cond.clone() * (one - cond.clone()),
+                s * (select(cond, x, y) - out),
+            ]
+        });
+
+        let a_equals_b = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_select_eq),
+            |meta| meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur()),
+            inv,
+        );
+
+        meta.create_gate("select by equality", |meta| {
+            let s = meta.query_selector(q_select_eq);
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (select(a_equals_b.expr(), x, y) - out)]
+        });
+
+        SelectConfig {
+            a,
+            b,
+            x,
+            y,
+            out,
+            q_select,
+            q_select_eq,
+            a_equals_b,
+            instance,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.a, 0, || value),
+        )
+    }
+
+    /// cond ? when_true : when_false, with cond a bit.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: &AssignedCell<F, F>,
+        when_true: &AssignedCell<F, F>,
+        when_false: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                self.config.q_select.enable(&mut region, 0)?;
+                let cond = cond.copy_advice(|| "cond", &mut region, self.config.a, 0)?;
+                let x = when_true.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                let y = when_false.copy_advice(|| "y", &mut region, self.config.y, 0)?;
+
+                let out = cond
+                    .value()
+                    .zip(x.value().zip(y.value()))
+                    .map(|(cond, (x, y))| if *cond == F::one() { *x } else { *y });
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    /// a == b ? x : y
+    pub fn select_by_equality(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        x: &AssignedCell<F, F>,
+        y: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero = IsZeroChip::construct(self.config.a_equals_b.clone());
+
+        layouter.assign_region(
+            || "select by equality",
+            |mut region| {
+                self.config.q_select_eq.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+                let x = x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                let y = y.copy_advice(|| "y", &mut region, self.config.y, 0)?;
+
+                let diff = a.value().copied() - b.value();
+                is_zero.assign(&mut region, 0, diff)?;
+
+                let out =
+                    diff.zip(x.value().zip(y.value()))
+                        .map(|(diff, (x, y))| if diff == F::zero() { *x } else { *y });
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // out = (a == b) ? c : (cond ? a : b), composed from both selects
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+        cond: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = SelectConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            SelectChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = SelectChip::construct(config);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            let c = chip.load_private(layouter.namespace(|| "c"), self.c)?;
+            let cond = chip.load_private(layouter.namespace(|| "cond"), self.cond)?;
+
+            let a_or_b = chip.select(layouter.namespace(|| "cond ? a : b"), &cond, &a, &b)?;
+            let out = chip.select_by_equality(
+                layouter.namespace(|| "a == b ? c : a_or_b"),
+                &a,
+                &b,
+                &c,
+                &a_or_b,
+            )?;
+
+            chip.expose_public(layouter.namespace(|| "out"), &out, 0)
+        }
+    }
+
+    fn run(a: u64, b: u64, c: u64, cond: u64, out: u64) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            c: Value::known(Fp::from(c)),
+            cond: Value::known(Fp::from(cond)),
+        };
+        MockProver::run(4, &circuit, vec![vec![Fp::from(out)]]).unwrap()
+    }
+
+    #[test]
+    fn test_select() {
+        run(10, 12, 15, 1, 10).assert_satisfied();
+        run(10, 12, 15, 0, 12).assert_satisfied();
+        run(10, 10, 15, 0, 15).assert_satisfied();
+        run(10, 10, 15, 1, 15).assert_satisfied();
+
+        assert!(run(10, 12, 15, 1, 12).verify().is_err());
+        assert!(run(10, 12, 15, 0, 15).verify().is_err());
+        assert!(run(10, 10, 15, 1, 10).verify().is_err());
+
+        // cond = 2 is not a bit
+        assert!(run(10, 12, 15, 2, 8).verify().is_err());
+    }
+}
@@ -0,0 +1,428 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// Proves that a private x belongs to a public set S = {e_1, ..., e_n}, with
+/// two strategies.
+///
+/// Product of differences: p = Π (x - e_i) is 0 exactly when x ∈ S, and the
+/// is-zero pattern from IsZeroChip turns p into a boolean cell:
+///
+///     x | e     | acc                  | out   | q_first | q_next | q_is_zero
+///    -------------------------------------------------------------------------
+///     x | e_1   | x - e_1              |       |    1    |   0    |    0
+///     x | e_2   | acc·(x - e_2)        |       |    0    |   1    |    0
+///     x | e_n   | acc·(x - e_n)        | p == 0|    0    |   1    |    1
+///
+/// Lookup: S is loaded into a fixed table like RangeTableConfig::load and x is
+/// looked up in it. The table is tagged so that the rows where q_lookup is off
+/// look up (0, 0) and do not require 0 ∈ S:
+///
+///     (q_lookup, q_lookup·x) ∈ {(0, 0)} ∪ {(1, e_i)}
+///
+/// The lookup costs one row per value but 2^k must exceed |S|. The product
+/// costs |S| rows per value.
+#[derive(Debug, Clone)]
+pub struct SetMembershipConfig<F> {
+    pub x: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub out: Column<Advice>,
+    pub element: Column<Fixed>,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub q_is_zero: Selector,
+    pub is_zero: IsZeroConfig<F>,
+    pub q_lookup: Selector,
+    pub table_tag: TableColumn,
+    pub table_value: TableColumn,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetMembershipChip<F: FieldExt> {
+    config: SetMembershipConfig<F>,
+}
+
+impl<F: FieldExt> SetMembershipChip<F> {
+    pub fn construct(config: SetMembershipConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> SetMembershipConfig<F> {
+        let x = meta.advice_column();
+        let acc = meta.advice_column();
+        let out = meta.advice_column();
+        let inv = meta.advice_column();
+        let element = meta.fixed_column();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let q_is_zero = meta.selector();
+        let q_lookup = meta.complex_selector();
+        let table_tag = meta.lookup_table_column();
+        let table_value = meta.lookup_table_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(x);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+
+        meta.create_gate("first difference", |meta| {
+            let s = meta.query_selector(q_first);
+            let x = meta.query_advice(x, Rotation::cur());
+            let e = meta.query_fixed(element, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (x - e - acc)]
+        });
+
+        meta.create_gate("product of differences", |meta| {
+            let s = meta.query_selector(q_next);
+            let x = meta.query_advice(x, Rotation::cur());
+            let e = meta.query_fixed(element, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (acc_prev * (x - e) - acc)]
+        });
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_is_zero),
+            |meta| meta.query_advice(acc, Rotation::cur()),
+            inv,
+        );
+
+        meta.create_gate("is member", |meta| {
+            let s = meta.query_selector(q_is_zero);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (out - is_zero.expr())]
+        });
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_lookup);
+            let x = meta.query_advice(x, Rotation::cur());
+            vec![(s.clone(), table_tag), (s * x, table_value)]
+        });
+
+        SetMembershipConfig {
+            x,
+            acc,
+            out,
+            element,
+            q_first,
+            q_next,
+            q_is_zero,
+            is_zero,
+            q_lookup,
+            table_tag,
+            table_value,
+            instance,
+        }
+    }
+
+    /// Loads the set for assert_member_lookup.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>, set: &[F]) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load set table",
+            |mut table| {
+                let entries = [(F::zero(), F::zero())]
+                    .into_iter()
+                    .chain(set.iter().map(|e| (F::one(), *e)));
+                for (offset, (tag, value)) in entries.enumerate() {
+                    table.assign_cell(
+                        || "tag",
+                        self.config.table_tag,
+                        offset,
+                        || Value::known(tag),
+                    )?;
+                    table.assign_cell(
+                        || "value",
+                        self.config.table_value,
+                        offset,
+                        || Value::known(value),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.x, 0, || value),
+        )
+    }
+
+    /// Returns the cell holding x ∈ set, with the product of differences.
+    pub fn is_member(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        set: &[F],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!set.is_empty());
+        let is_zero = IsZeroChip::construct(self.config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is member",
+            |mut region| {
+                let mut acc = Value::known(F::one());
+
+                for (row, e) in set.iter().enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_next.enable(&mut region, row)?;
+                    }
+
+                    x.copy_advice(|| "x", &mut region, self.config.x, row)?;
+                    region.assign_fixed(|| "e", self.config.element, row, || Value::known(*e))?;
+                    acc = acc.zip(x.value()).map(|(acc, x)| acc * (*x - e));
+                    region.assign_advice(|| "acc", self.config.acc, row, || acc)?;
+                }
+
+                let last = set.len() - 1;
+                self.config.q_is_zero.enable(&mut region, last)?;
+                is_zero.assign(&mut region, last, acc)?;
+
+                let out = acc.map(|acc| F::from((acc == F::zero()) as u64));
+                region.assign_advice(|| "x in set", self.config.out, last, || out)
+            },
+        )
+    }
+
+    /// Constrains x ∈ set, with a lookup into the table from load_table.
+    pub fn assert_member_lookup(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "lookup member",
+            |mut region| {
+                self.config.q_lookup.enable(&mut region, 0)?;
+                x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Layout;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        pasta::{EqAffine, Fp},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand_core::OsRng;
+    use std::time::Instant;
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    enum Strategy {
+        #[default]
+        Product,
+        Lookup,
+    }
+
+    // Checks every value against the set. With Product the membership bits are
+    // exposed, with Lookup every value must be a member.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        values: Vec<Value<F>>,
+        set: Vec<F>,
+        strategy: Strategy,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = SetMembershipConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                set: self.set.clone(),
+                strategy: self.strategy,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            SetMembershipChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = SetMembershipChip::construct(config);
+            if self.strategy == Strategy::Lookup {
+                chip.load_table(&mut layouter, &self.set)?;
+            }
+
+            for (row, value) in self.values.iter().enumerate() {
+                let x = chip.load_private(layouter.namespace(|| "x"), *value)?;
+                match self.strategy {
+                    Strategy::Product => {
+                        let out =
+                            chip.is_member(layouter.namespace(|| "x in set"), &x, &self.set)?;
+                        chip.expose_public(layouter.namespace(|| "x in set"), &out, row)?;
+                    }
+                    Strategy::Lookup => {
+                        chip.assert_member_lookup(layouter.namespace(|| "x in set"), &x)?;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn circuit(values: &[u64], set: &[u64], strategy: Strategy) -> MyCircuit<Fp> {
+        MyCircuit {
+            values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+            set: set.iter().map(|e| Fp::from(*e)).collect(),
+            strategy,
+        }
+    }
+
+    // Primes below 100
+    const SET: [u64; 25] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+        97,
+    ];
+
+    #[test]
+    fn test_set_membership_product() {
+        let run = |values: &[u64], members: &[u64]| {
+            let circuit = circuit(values, &SET, Strategy::Product);
+            let public_input = members.iter().map(|v| Fp::from(*v)).collect();
+            MockProver::run(8, &circuit, vec![public_input]).unwrap()
+        };
+
+        run(&[2, 97, 53, 0, 1, 98, 51], &[1, 1, 1, 0, 0, 0, 0]).assert_satisfied();
+
+        assert!(run(&[51], &[1]).verify().is_err());
+        assert!(run(&[53], &[0]).verify().is_err());
+    }
+
+    #[test]
+    fn test_set_membership_lookup() {
+        let run = |values: &[u64]| {
+            let circuit = circuit(values, &SET, Strategy::Lookup);
+            MockProver::run(6, &circuit, vec![vec![]]).unwrap()
+        };
+
+        run(&[2, 97, 53, 2]).assert_satisfied();
+
+        // 0 is not in the set, even though disabled rows look up (0, 0)
+        assert!(run(&[0]).verify().is_err());
+        assert!(run(&[51]).verify().is_err());
+        assert!(run(&[2, 1]).verify().is_err());
+    }
+
+    #[test]
+    fn test_set_membership_layout() {
+        let set: Vec<u64> = (0..500).map(|i| i * 7 + 1).collect();
+        let values: Vec<u64> = (0..8).map(|i| set[i * 60]).collect();
+
+        // The product uses |S| rows per value plus the row loading it
+        let product = Layout::measure(&circuit(&values, &set, Strategy::Product)).unwrap();
+        assert_eq!(product.rows, values.len() * (set.len() + 1));
+
+        // The lookup only needs the |S| + 1 rows of the table, and its two rows
+        // per value fit beside them
+        let lookup = Layout::measure(&circuit(&values, &set, Strategy::Lookup)).unwrap();
+        assert_eq!(lookup.rows, set.len() + 1);
+    }
+
+    // Times keygen and a real proof for both strategies, where the layout test
+    // above only counts rows. Run it with
+    //
+    //     cargo test --release -- --ignored --nocapture bench_set_membership
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_set_membership() {
+        let set: Vec<u64> = (0..4096).map(|i| i * 7 + 1).collect();
+        let values: Vec<u64> = (0..8).map(|i| set[i * 500]).collect();
+
+        for strategy in [Strategy::Product, Strategy::Lookup] {
+            let circuit = circuit(&values, &set, strategy);
+            let public_input: Vec<Fp> = match strategy {
+                Strategy::Product => vec![Fp::one(); values.len()],
+                Strategy::Lookup => vec![],
+            };
+
+            // Smallest k with room for the layout and the blinding rows
+            let mut cs = ConstraintSystem::default();
+            MyCircuit::<Fp>::configure(&mut cs);
+            let rows = Layout::measure(&circuit).unwrap().rows + cs.minimum_rows();
+            let k = rows.next_power_of_two().trailing_zeros();
+            let params: Params<EqAffine> = Params::new(k);
+
+            let start = Instant::now();
+            let vk = keygen_vk(&params, &circuit.without_witnesses()).unwrap();
+            let pk = keygen_pk(&params, vk, &circuit.without_witnesses()).unwrap();
+            let keygen = start.elapsed();
+
+            let start = Instant::now();
+            let instances: &[&[Fp]] = &[&public_input];
+            let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+            create_proof(
+                &params,
+                &pk,
+                &[circuit],
+                &[instances],
+                OsRng,
+                &mut transcript,
+            )
+            .unwrap();
+            let proof = transcript.finalize();
+            let prove = start.elapsed();
+
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            let verifier = SingleVerifier::new(&params);
+            verify_proof(
+                &params,
+                pk.get_vk(),
+                verifier,
+                &[instances],
+                &mut transcript,
+            )
+            .unwrap();
+
+            println!(
+                "{:?}, |S| = {}, {} values: k = {}, keygen {:?}, prove {:?}",
+                strategy,
+                set.len(),
+                values.len(),
+                k,
+                keygen,
+                prove
+            );
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use crate::less_than::{LtChip, LtConfig};
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Signed integers of N bytes (i8, i16, i32, i64 for N = 1, 2, 4, 8). A value x
+/// is the field element x itself, so -1 is p - 1. The range and the order come
+/// from the offset encoding
+///
+///     y = x + 2^(8N-1)
+///
+/// which maps MIN..=MAX onto 0..2^(8N) in order, so LtChip can range check and
+/// compare y as unsigned. -1 = p - 1 wraps around the field to 2^(8N-1) - 1,
+/// and every field element outside MIN..=MAX lands outside 0..2^(8N).
+///
+///     x | y               | sign | out          | selector
+///    ------------------------------------------------------
+///     x | x + 2^(8N-1)    |      |              | q_encode
+///     x |                 |      | -x           | q_neg
+///     x |                 | x<0  | x·(1-2·sign) | q_abs
+///
+/// neg and abs range check their output, so -MIN and abs(MIN), which do not
+/// fit, leave the circuit unsatisfied.
+#[derive(Debug, Clone)]
+pub(super) struct SignedConfig<F: FieldExt, const N: usize> {
+    pub x: Column<Advice>,
+    pub y: Column<Advice>,
+    pub sign: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_encode: Selector,
+    pub q_neg: Selector,
+    pub q_abs: Selector,
+    pub(super) lt: LtConfig<F, N>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SignedChip<F: FieldExt, const N: usize> {
+    config: SignedConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> SignedChip<F, N> {
+    pub fn construct(config: SignedConfig<F, N>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, 256>,
+    ) -> SignedConfig<F, N> {
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let sign = meta.advice_column();
+        let out = meta.advice_column();
+        let q_encode = meta.selector();
+        let q_neg = meta.selector();
+        let q_abs = meta.selector();
+        let lt = LtChip::configure(meta, table);
+
+        for column in [x, y, sign, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("offset encoding", |meta| {
+            let s = meta.query_selector(q_encode);
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            vec![s * (x + Expression::Constant(Self::half()) - y)]
+        });
+
+        meta.create_gate("negation", |meta| {
+            let s = meta.query_selector(q_neg);
+            let x = meta.query_advice(x, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![s * (x + out)]
+        });
+
+        meta.create_gate("absolute value", |meta| {
+            let s = meta.query_selector(q_abs);
+            let x = meta.query_advice(x, Rotation::cur());
+            let sign = meta.query_advice(sign, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let two = Expression::Constant(F::from(2));
+            vec![s * (x.clone() - two * sign * x - out)]
+        });
+
+        SignedConfig {
+            x,
+            y,
+            sign,
+            out,
+            q_encode,
+            q_neg,
+            q_abs,
+            lt,
+        }
+    }
+
+    /// The field element for x, with negative x as p - |x|.
+    pub fn to_field(x: i64) -> F {
+        match x < 0 {
+            true => -F::from(x.unsigned_abs()),
+            false => F::from(x as u64),
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.x, 0, || value),
+        )
+    }
+
+    /// Constrains MIN <= x <= MAX.
+    pub fn assert_in_range(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        x: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.encode(layouter, tables, x)?;
+        Ok(())
+    }
+
+    /// a < b
+    pub fn lt(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let a = self.encode(layouter.namespace(|| "encode a"), tables, a)?;
+        let b = self.encode(layouter.namespace(|| "encode b"), tables, b)?;
+        self.lt_chip()
+            .lt(layouter.namespace(|| "a < b"), tables, &a, &b)
+    }
+
+    /// a <= b
+    pub fn le(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let a = self.encode(layouter.namespace(|| "encode a"), tables, a)?;
+        let b = self.encode(layouter.namespace(|| "encode b"), tables, b)?;
+        self.lt_chip()
+            .le(layouter.namespace(|| "a <= b"), tables, &a, &b)
+    }
+
+    /// a > b
+    pub fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.lt(layouter, tables, b, a)
+    }
+
+    /// a >= b
+    pub fn ge(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.le(layouter, tables, b, a)
+    }
+
+    /// -x, unsatisfiable for x = MIN.
+    pub fn neg(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        x: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let out = layouter.assign_region(
+            || "-x",
+            |mut region| {
+                self.config.q_neg.enable(&mut region, 0)?;
+                let x = x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                region.assign_advice(|| "-x", self.config.out, 0, || -x.value().copied())
+            },
+        )?;
+        self.assert_in_range(layouter.namespace(|| "-x in range"), tables, &out)?;
+        Ok(out)
+    }
+
+    /// |x|, unsatisfiable for x = MIN.
+    pub fn abs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        x: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let lt = self.lt_chip();
+
+        // x < 0 exactly when y < 2^(8N-1)
+        let y = self.encode(layouter.namespace(|| "encode x"), tables, x)?;
+        let half = layouter.assign_region(
+            || "2^(8N-1)",
+            |mut region| {
+                region.assign_advice_from_constant(|| "half", self.config.y, 0, Self::half())
+            },
+        )?;
+        let sign = lt.lt(layouter.namespace(|| "x < 0"), tables, &y, &half)?;
+
+        let out = layouter.assign_region(
+            || "|x|",
+            |mut region| {
+                self.config.q_abs.enable(&mut region, 0)?;
+                let x = x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                let sign = sign.copy_advice(|| "sign", &mut region, self.config.sign, 0)?;
+                let out = x
+                    .value()
+                    .zip(sign.value())
+                    .map(|(x, sign)| match *sign == F::one() {
+                        true => -*x,
+                        false => *x,
+                    });
+                region.assign_advice(|| "|x|", self.config.out, 0, || out)
+            },
+        )?;
+        self.assert_in_range(layouter.namespace(|| "|x| in range"), tables, &out)?;
+        Ok(out)
+    }
+
+    pub fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        self.lt_chip().expose_public(layouter, cell, row)
+    }
+
+    // Returns the cell holding y = x + 2^(8N-1), range checked to 0..2^(8N)
+    fn encode(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        x: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let y = layouter.assign_region(
+            || "offset encoding",
+            |mut region| {
+                self.config.q_encode.enable(&mut region, 0)?;
+                let x = x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                let y = x.value().map(|x| *x + Self::half());
+                region.assign_advice(|| "y", self.config.y, 0, || y)
+            },
+        )?;
+        self.lt_chip()
+            .range_check(layouter.namespace(|| "y in range"), tables, &y)?;
+        Ok(y)
+    }
+
+    fn lt_chip(&self) -> LtChip<F, N> {
+        LtChip::construct(self.config.lt.clone())
+    }
+
+    fn half() -> F {
+        F::from_u128(1 << (8 * N - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    #[derive(Clone, Copy, Debug, Default)]
+    enum Op {
+        // Exposes a < b, a <= b, a > b, a >= b
+        #[default]
+        Compare,
+        Neg,
+        Abs,
+    }
+
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt, const N: usize> {
+        a: Value<F>,
+        b: Value<F>,
+        op: Op,
+    }
+
+    impl<F: FieldExt, const N: usize> Circuit<F> for MyCircuit<F, N> {
+        type Config = (SignedConfig<F, N>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (SignedChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = SignedChip::<F, N>::construct(config);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            chip.assert_in_range(layouter.namespace(|| "a in range"), &tables, &a)?;
+            chip.assert_in_range(layouter.namespace(|| "b in range"), &tables, &b)?;
+
+            let results = match self.op {
+                Op::Compare => vec![
+                    chip.lt(layouter.namespace(|| "a < b"), &tables, &a, &b)?,
+                    chip.le(layouter.namespace(|| "a <= b"), &tables, &a, &b)?,
+                    chip.gt(layouter.namespace(|| "a > b"), &tables, &a, &b)?,
+                    chip.ge(layouter.namespace(|| "a >= b"), &tables, &a, &b)?,
+                ],
+                Op::Neg => vec![chip.neg(layouter.namespace(|| "-a"), &tables, &a)?],
+                Op::Abs => vec![chip.abs(layouter.namespace(|| "|a|"), &tables, &a)?],
+            };
+
+            for (row, result) in results.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "result"), result, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    // Also takes values outside i64
+    fn field(x: i128) -> Fp {
+        match x < 0 {
+            true => -Fp::from_u128(x.unsigned_abs()),
+            false => Fp::from_u128(x as u128),
+        }
+    }
+
+    fn run<const N: usize>(a: i128, b: i128, op: Op, public_input: &[i128]) -> MockProver<Fp> {
+        let circuit = MyCircuit::<Fp, N> {
+            a: Value::known(field(a)),
+            b: Value::known(field(b)),
+            op,
+        };
+        let public_input = public_input.iter().map(|x| field(*x)).collect();
+        MockProver::run(10, &circuit, vec![public_input]).unwrap()
+    }
+
+    // MIN, MIN + 1, -1, 0, 1, MAX - 1, MAX of N bytes
+    fn edge_values<const N: usize>() -> [i128; 7] {
+        let max = (1i128 << (8 * N - 1)) - 1;
+        [-max - 1, -max, -1, 0, 1, max - 1, max]
+    }
+
+    fn test_signed<const N: usize>() {
+        let values = edge_values::<N>();
+        let (min, max) = (values[0], values[6]);
+
+        for a in values {
+            for b in values {
+                let expected = [a < b, a <= b, a > b, a >= b].map(|x| x as i128);
+                run::<N>(a, b, Op::Compare, &expected).assert_satisfied();
+            }
+
+            if a != min {
+                run::<N>(a, 0, Op::Neg, &[-a]).assert_satisfied();
+                run::<N>(a, 0, Op::Abs, &[a.abs()]).assert_satisfied();
+            }
+        }
+
+        assert!(run::<N>(-5, 0, Op::Abs, &[-5]).verify().is_err());
+        assert!(run::<N>(5, 0, Op::Neg, &[5]).verify().is_err());
+
+        // -MIN and |MIN| are MAX + 1
+        assert!(run::<N>(min, 0, Op::Neg, &[max + 1]).verify().is_err());
+        assert!(run::<N>(min, 0, Op::Abs, &[max + 1]).verify().is_err());
+
+        // Just outside the range
+        assert!(run::<N>(max + 1, 0, Op::Neg, &[-max - 1]).verify().is_err());
+        assert!(run::<N>(min - 1, 0, Op::Neg, &[max + 2]).verify().is_err());
+
+        // -1 is not MAX, even though p - 1 is the largest field element
+        assert!(run::<N>(-1, max, Op::Compare, &[0, 0, 1, 1])
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn test_signed_i8() {
+        test_signed::<1>();
+    }
+
+    #[test]
+    fn test_signed_i16() {
+        test_signed::<2>();
+    }
+
+    #[test]
+    fn test_signed_i32() {
+        test_signed::<4>();
+    }
+
+    #[test]
+    fn test_signed_i64() {
+        test_signed::<8>();
+
+        let chip_field = SignedChip::<Fp, 8>::to_field;
+        assert_eq!(chip_field(-1), field(-1));
+        assert_eq!(chip_field(i64::MIN), field(i64::MIN as i128));
+        assert_eq!(chip_field(i64::MAX), field(i64::MAX as i128));
+    }
+}
@@ -0,0 +1,355 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use crate::less_than::{LtChip, LtConfig};
+use crate::table::{RangeTableConfig, TableLoads};
+
+/// Proves that output is input sorted in ascending order, for integers of N
+/// bytes. Order is out_i <= out_i+1 for adjacent outputs, with LtChip. The
+/// multisets are equal when
+///
+///     Π (γ - in_i) = Π (γ - out_i)
+///
+/// for a random γ, which the running quotient acc checks row by row:
+///
+///     in   | out   | γ | acc                                  | q_first | q_next
+///    ---------------------------------------------------------------------------
+///     in_0 | out_0 | γ | (γ - in_0) / (γ - out_0)             |    1    |   0
+///     in_1 | out_1 | γ | acc·(γ - in_1) / (γ - out_1)         |    0    |   1
+///     in_n | out_n | γ | 1                                    |    0    |   1
+///
+/// This halo2 version has no verifier challenges, so γ is a public input. It
+/// is only sound if the prover cannot choose the input after seeing γ, e.g. γ
+/// is derived from a commitment to the input that is fixed beforehand.
+///
+/// The gate is acc_i·(γ - out_i) = acc_i-1·(γ - in_i), so at a row with
+/// γ = out_i, and then γ = in_i or acc_i-1 = 0, acc_i is unconstrained. A
+/// prover who knows γ could put it at such a row and restart the product from
+/// there. This is another reason γ must not be known when the input is chosen:
+/// for a random γ it hits a value with negligible probability.
+#[derive(Debug, Clone)]
+pub(super) struct SortConfig<F: FieldExt, const N: usize> {
+    pub input: Column<Advice>,
+    pub output: Column<Advice>,
+    pub gamma: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub(super) lt: LtConfig<F, N>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SortChip<F: FieldExt, const N: usize> {
+    config: SortConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> SortChip<F, N> {
+    pub fn construct(config: SortConfig<F, N>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: RangeTableConfig<F, 256>,
+    ) -> SortConfig<F, N> {
+        let input = meta.advice_column();
+        let output = meta.advice_column();
+        let gamma = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let lt = LtChip::configure(meta, table);
+
+        meta.enable_equality(input);
+        meta.enable_equality(output);
+        meta.enable_equality(gamma);
+        meta.enable_equality(acc);
+
+        meta.create_gate("first quotient", |meta| {
+            let s = meta.query_selector(q_first);
+            let input = meta.query_advice(input, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+            let gamma = meta.query_advice(gamma, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (acc * (gamma.clone() - output) - (gamma - input))]
+        });
+
+        meta.create_gate("running quotient", |meta| {
+            let s = meta.query_selector(q_next);
+            let input = meta.query_advice(input, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+            let gamma_prev = meta.query_advice(gamma, Rotation::prev());
+            let gamma = meta.query_advice(gamma, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![
+                s.clone() * (acc * (gamma.clone() - output) - acc_prev * (gamma.clone() - input)),
+                s * (gamma - gamma_prev),
+            ]
+        });
+
+        SortConfig {
+            input,
+            output,
+            gamma,
+            acc,
+            q_first,
+            q_next,
+            lt,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", self.config.input, 0, || value),
+        )
+    }
+
+    pub fn load_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "load public",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "public input",
+                    self.config.lt.instance,
+                    row,
+                    self.config.gamma,
+                    0,
+                )
+            },
+        )
+    }
+
+    /// Returns the cells holding input sorted in ascending order, constrained
+    /// to be a sorted permutation of input.
+    pub fn sort(
+        &self,
+        layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        input: &[AssignedCell<F, F>],
+        gamma: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(!input.is_empty());
+
+        let sorted: Value<Vec<F>> = input.iter().map(|cell| cell.value().copied()).collect();
+        let sorted = sorted
+            .map(|mut values| {
+                values.sort_by_key(|value| value.get_lower_128());
+                values
+            })
+            .transpose_vec(input.len());
+
+        self.assign_sorted(layouter, tables, input, &sorted, gamma)
+    }
+
+    // Sorting is only witness generation, so this takes any claimed output
+    fn assign_sorted(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tables: &TableLoads,
+        input: &[AssignedCell<F, F>],
+        sorted: &[Value<F>],
+        gamma: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert_eq!(input.len(), sorted.len());
+        let lt = LtChip::construct(self.config.lt.clone());
+
+        let output = layouter.assign_region(
+            || "sorted permutation",
+            |mut region| {
+                let mut acc = Value::known(F::one());
+                let mut acc_cell = None;
+                let mut output = vec![];
+
+                for (row, (cell, out)) in input.iter().zip(sorted.iter()).enumerate() {
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_next.enable(&mut region, row)?;
+                    }
+
+                    let in_cell = cell.copy_advice(|| "in", &mut region, self.config.input, row)?;
+                    let out_cell =
+                        region.assign_advice(|| "out", self.config.output, row, || *out)?;
+                    let gamma = gamma.copy_advice(|| "γ", &mut region, self.config.gamma, row)?;
+
+                    // γ equal to an output has no inverse. acc = 0 from there
+                    // on, so the last acc != 1 and the circuit fails to verify
+                    acc = acc
+                        .zip(gamma.value().zip(in_cell.value().zip(out_cell.value())))
+                        .map(|(acc, (gamma, (input, output)))| {
+                            let inv = (*gamma - output).invert().unwrap_or(F::zero());
+                            acc * (*gamma - input) * inv
+                        });
+                    acc_cell =
+                        Some(region.assign_advice(|| "acc", self.config.acc, row, || acc)?);
+                    output.push(out_cell);
+                }
+
+                region.constrain_constant(acc_cell.unwrap().cell(), F::one())?;
+                Ok(output)
+            },
+        )?;
+
+        // Every output is in range, so the comparisons hold
+        for out in output.iter() {
+            lt.range_check(layouter.namespace(|| "out in range"), tables, out)?;
+        }
+        for pair in output.windows(2) {
+            lt.assert_le(
+                layouter.namespace(|| "out_i <= out_i+1"),
+                tables,
+                &pair[0],
+                &pair[1],
+            )?;
+        }
+
+        Ok(output)
+    }
+
+    pub fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        LtChip::construct(self.config.lt.clone()).expose_public(layouter, cell, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    // Instance: γ, then the sorted values. A claimed output replaces the one
+    // sort computes, as a malicious prover would.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        values: Vec<Value<F>>,
+        claimed: Option<Vec<Value<F>>>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = (SortConfig<F, 2>, RangeTableConfig<F, 256>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                claimed: None,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = RangeTableConfig::configure(meta);
+            (SortChip::configure(meta, table.clone()), table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, table) = config;
+            let tables = TableLoads::default();
+            table.load(&mut layouter, &tables)?;
+            let chip = SortChip::construct(config);
+
+            let gamma = chip.load_public(layouter.namespace(|| "γ"), 0)?;
+            let input = self
+                .values
+                .iter()
+                .map(|v| chip.load_private(layouter.namespace(|| "in"), *v))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let output = match &self.claimed {
+                Some(claimed) => chip.assign_sorted(
+                    layouter.namespace(|| "sort"),
+                    &tables,
+                    &input,
+                    claimed,
+                    &gamma,
+                )?,
+                None => chip.sort(layouter.namespace(|| "sort"), &tables, &input, &gamma)?,
+            };
+
+            for (row, out) in output.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), out, row + 1)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    const GAMMA: u64 = 0x1234_5678_9abc_def1;
+
+    fn known(values: &[u64]) -> Vec<Value<Fp>> {
+        values.iter().map(|v| Value::known(Fp::from(*v))).collect()
+    }
+
+    fn public_input(sorted: &[u64]) -> Vec<Fp> {
+        [GAMMA].iter().chain(sorted).map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let run = |values: &[u64], sorted: &[u64]| {
+            let circuit = MyCircuit {
+                values: known(values),
+                claimed: None,
+            };
+            MockProver::run(10, &circuit, vec![public_input(sorted)]).unwrap()
+        };
+
+        run(&[7], &[7]).assert_satisfied();
+        run(&[300, 2, 65535, 0, 2, 41], &[0, 2, 2, 41, 300, 65535]).assert_satisfied();
+
+        assert!(run(&[3, 1, 2], &[1, 3, 2]).verify().is_err());
+        // Out of range for two bytes
+        assert!(run(&[1 << 16, 1], &[1, 1 << 16]).verify().is_err());
+    }
+
+    #[test]
+    fn test_sort_malicious_output() {
+        let run = |values: &[u64], claimed: &[u64]| {
+            let circuit = MyCircuit {
+                values: known(values),
+                claimed: Some(known(claimed)),
+            };
+            MockProver::run(10, &circuit, vec![public_input(claimed)]).unwrap()
+        };
+
+        run(&[3, 1, 2], &[1, 2, 3]).assert_satisfied();
+
+        // Not sorted
+        assert!(run(&[3, 1, 2], &[1, 3, 2]).verify().is_err());
+        // Not a permutation
+        assert!(run(&[3, 1, 2], &[1, 2, 4]).verify().is_err());
+        assert!(run(&[3, 1, 2], &[1, 2, 2]).verify().is_err());
+    }
+
+    #[test]
+    fn test_sort_gamma_collision() {
+        // γ = 2 is one of the outputs, so the quotient has no inverse
+        let circuit = MyCircuit {
+            values: known(&[3, 1, 2]),
+            claimed: None,
+        };
+        let public_input = [2, 1, 2, 3].map(Fp::from).to_vec();
+        let prover = MockProver::run(10, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{cell::RefCell, fmt, marker::PhantomData};
 
 use halo2_proofs::{
     arithmetic::FieldExt,
@@ -6,7 +6,64 @@ use halo2_proofs::{
     plonk::{ConstraintSystem, Error, TableColumn},
 };
 
+/// A misused range table, found by the TableLoads checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TableError {
+    /// The table was loaded a second time in one synthesis.
+    LoadedTwice,
+    /// A lookup into the table was assigned but the table was never loaded.
+    NotLoaded,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::LoadedTwice => write!(f, "range table loaded twice in one synthesis"),
+            TableError::NotLoaded => write!(f, "range table used before it is loaded"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+// Synthesis can only return a halo2 Error
+impl From<TableError> for Error {
+    fn from(_: TableError) -> Self {
+        Error::Synthesis
+    }
+}
+
+/// The load token of one synthesis: the range tables loaded so far.
+///
+/// Create a fresh one at the start of every synthesize, load each table
+/// through it, and pass it to every config that assigns lookups into a table.
+/// The configs check their table was loaded before assigning a lookup, since
+/// an unloaded table is all zeros and a lookup of 0 would pass silently. It
+/// lives for a single synthesis, so V1's two passes and a config reused across
+/// proofs each start from nothing.
+#[derive(Debug, Default)]
+pub(super) struct TableLoads {
+    loaded: RefCell<Vec<TableColumn>>,
+}
+
+impl TableLoads {
+    fn insert(&self, column: TableColumn) -> Result<(), TableError> {
+        if self.contains(column) {
+            return Err(TableError::LoadedTwice);
+        }
+        self.loaded.borrow_mut().push(column);
+        Ok(())
+    }
+
+    fn contains(&self, column: TableColumn) -> bool {
+        self.loaded.borrow().contains(&column)
+    }
+}
+
 /// A lookup table of values from 0..RANGE.
+///
+/// Configure it once and pass clones to every config that looks up into it,
+/// then load it exactly once per synthesis with a TableLoads.
 #[derive(Debug, Clone)]
 pub(super) struct RangeTableConfig<F: FieldExt, const RANGE: usize> {
     pub(super) value: TableColumn,
@@ -23,7 +80,12 @@ impl<F: FieldExt, const RANGE: usize> RangeTableConfig<F, RANGE> {
         }
     }
 
-    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    pub(super) fn load(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        tables: &TableLoads,
+    ) -> Result<(), Error> {
+        tables.insert(self.value)?;
         layouter.assign_table(
             || "load range-check table",
             |mut table| {
@@ -42,4 +104,125 @@ impl<F: FieldExt, const RANGE: usize> RangeTableConfig<F, RANGE> {
             },
         )
     }
-}
\ No newline at end of file
+
+    /// Called by configs before assigning a lookup into the table.
+    pub(super) fn check_loaded(&self, tables: &TableLoads) -> Result<(), TableError> {
+        if tables.contains(self.value) {
+            Ok(())
+        } else {
+            Err(TableError::NotLoaded)
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum BitwiseOp {
+    Xor,
+    And,
+    Or,
+}
+
+impl BitwiseOp {
+    pub(super) const ALL: [BitwiseOp; 3] = [BitwiseOp::Xor, BitwiseOp::And, BitwiseOp::Or];
+
+    // Xor is 0, so a disabled lookup of all zeros matches 0 ^ 0 = 0
+    pub(super) fn tag(&self) -> u64 {
+        match self {
+            BitwiseOp::Xor => 0,
+            BitwiseOp::And => 1,
+            BitwiseOp::Or => 2,
+        }
+    }
+
+    pub(super) fn apply(&self, a: u64, b: u64) -> u64 {
+        match self {
+            BitwiseOp::Xor => a ^ b,
+            BitwiseOp::And => a & b,
+            BitwiseOp::Or => a | b,
+        }
+    }
+}
+
+/// A lookup table of (tag, a, b, a op b) for every op and all a, b in
+/// 0..2^BITS, i.e. 3·2^(2·BITS) rows.
+#[derive(Debug, Clone)]
+pub(super) struct BitwiseTableConfig<F: FieldExt, const BITS: usize> {
+    pub(super) tag: TableColumn,
+    pub(super) a: TableColumn,
+    pub(super) b: TableColumn,
+    pub(super) out: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseTableConfig<F, BITS> {
+    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            tag: meta.lookup_table_column(),
+            a: meta.lookup_table_column(),
+            b: meta.lookup_table_column(),
+            out: meta.lookup_table_column(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load bitwise table",
+            |mut table| {
+                let mut offset = 0;
+                for op in BitwiseOp::ALL {
+                    for a in 0..1 << BITS {
+                        for b in 0..1 << BITS {
+                            let row = [
+                                (self.tag, op.tag()),
+                                (self.a, a),
+                                (self.b, b),
+                                (self.out, op.apply(a, b)),
+                            ];
+                            for (column, value) in row {
+                                table.assign_cell(
+                                    || "bitwise",
+                                    column,
+                                    offset,
+                                    || Value::known(F::from(value)),
+                                )?;
+                            }
+                            offset += 1;
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::pasta::Fp;
+
+    #[test]
+    fn test_table_loads() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let a = RangeTableConfig::<Fp, 256>::configure(&mut meta);
+        let b = RangeTableConfig::<Fp, 256>::configure(&mut meta);
+        let tables = TableLoads::default();
+
+        assert_eq!(a.check_loaded(&tables), Err(TableError::NotLoaded));
+        assert_eq!(tables.insert(a.value), Ok(()));
+        assert_eq!(a.check_loaded(&tables), Ok(()));
+        assert_eq!(tables.insert(a.value), Err(TableError::LoadedTwice));
+
+        // Each table is loaded on its own
+        assert_eq!(b.check_loaded(&tables), Err(TableError::NotLoaded));
+        assert_eq!(tables.insert(b.value), Ok(()));
+        assert_eq!(b.check_loaded(&tables), Ok(()));
+
+        // A new synthesis starts from nothing
+        assert_eq!(
+            a.check_loaded(&TableLoads::default()),
+            Err(TableError::NotLoaded)
+        );
+    }
+}